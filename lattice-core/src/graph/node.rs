@@ -3,7 +3,7 @@
 //! This module defines the node types that live in the dependency graph.
 
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 /// Unique identifier for a node in the dependency graph.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -83,6 +83,28 @@ pub struct Node {
     /// Nodes that depend on this node (children in the DAG).
     /// For a signal, these are the memos/effects that read from it.
     dependents: HashSet<NodeId>,
+
+    /// Weak dependencies: nodes this node can read from without being kept
+    /// dirty on their account. Tracked separately from `dependencies` so the
+    /// scheduler's in-degree counts and `mark_changed` propagation never see
+    /// them - see `UpdateScheduler::add_weak_edge`.
+    weak_dependencies: HashSet<NodeId>,
+
+    /// The inverse of `weak_dependencies`: nodes that read this node weakly.
+    weak_dependents: HashSet<NodeId>,
+
+    /// This node's most recently computed output, as raw bytes - see
+    /// `UpdateScheduler::propagate_pull` for why a byte buffer rather than a
+    /// generic `T`: the graph module tracks dependency structure only and
+    /// has no type information for what a node actually computes, so values
+    /// are compared byte-for-byte rather than via `PartialEq`.
+    output: Option<Vec<u8>>,
+
+    /// Per dependency, the value of `output` this node last consumed from it.
+    /// Comparing a dependency's current `output` against its entry here is
+    /// what lets `propagate_pull` tell "pushed dirty" apart from "actually
+    /// changed".
+    consumed: HashMap<NodeId, Vec<u8>>,
 }
 
 impl Node {
@@ -98,6 +120,10 @@ impl Node {
             },
             dependencies: HashSet::new(),
             dependents: HashSet::new(),
+            weak_dependencies: HashSet::new(),
+            weak_dependents: HashSet::new(),
+            output: None,
+            consumed: HashMap::new(),
         }
     }
 
@@ -187,6 +213,58 @@ impl Node {
     pub fn clear_dependencies(&mut self) {
         self.dependencies.clear();
     }
+
+    /// Add a weak dependency: read-if-present, not kept alive or recomputed
+    /// on this node's account (see `UpdateScheduler::add_weak_edge`).
+    pub fn add_weak_dependency(&mut self, node_id: NodeId) {
+        self.weak_dependencies.insert(node_id);
+    }
+
+    /// Remove a weak dependency.
+    pub fn remove_weak_dependency(&mut self, node_id: NodeId) {
+        self.weak_dependencies.remove(&node_id);
+    }
+
+    /// Get all weak dependencies.
+    pub fn weak_dependencies(&self) -> &HashSet<NodeId> {
+        &self.weak_dependencies
+    }
+
+    /// Add a weak dependent (a node that weakly reads from this node).
+    pub fn add_weak_dependent(&mut self, node_id: NodeId) {
+        self.weak_dependents.insert(node_id);
+    }
+
+    /// Remove a weak dependent.
+    pub fn remove_weak_dependent(&mut self, node_id: NodeId) {
+        self.weak_dependents.remove(&node_id);
+    }
+
+    /// Get all weak dependents.
+    pub fn weak_dependents(&self) -> &HashSet<NodeId> {
+        &self.weak_dependents
+    }
+
+    /// This node's most recently recomputed output, if it has ever been set.
+    pub fn output(&self) -> Option<&[u8]> {
+        self.output.as_deref()
+    }
+
+    /// Cache this node's freshly recomputed output.
+    pub fn set_output(&mut self, value: Vec<u8>) {
+        self.output = Some(value);
+    }
+
+    /// The value of `dependency`'s output this node last consumed, if any.
+    pub fn last_consumed(&self, dependency: NodeId) -> Option<&[u8]> {
+        self.consumed.get(&dependency).map(Vec::as_slice)
+    }
+
+    /// Record the value consumed from `dependency` during this node's most
+    /// recent recomputation.
+    pub fn record_consumed(&mut self, dependency: NodeId, value: Vec<u8>) {
+        self.consumed.insert(dependency, value);
+    }
 }
 
 #[cfg(test)]
@@ -232,6 +310,21 @@ mod tests {
         assert_eq!(node.dependencies().len(), 1);
     }
 
+    #[test]
+    fn output_and_consumed_value_tracking() {
+        let mut node = Node::derived();
+        let dep = NodeId::new();
+
+        assert_eq!(node.output(), None);
+        assert_eq!(node.last_consumed(dep), None);
+
+        node.set_output(vec![1, 2, 3]);
+        assert_eq!(node.output(), Some(&[1, 2, 3][..]));
+
+        node.record_consumed(dep, vec![4, 5]);
+        assert_eq!(node.last_consumed(dep), Some(&[4, 5][..]));
+    }
+
     #[test]
     fn dirty_state_transitions() {
         let mut node = Node::derived();
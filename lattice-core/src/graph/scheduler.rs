@@ -16,11 +16,59 @@
 //!    - For "dirty" nodes: recompute
 //!    - If output changed, mark dependents as dirty
 //!
-//! This "push-pull" approach minimizes unnecessary recomputation.
+//! This "push-pull" approach minimizes unnecessary recomputation: pushing
+//! dirty flags alone (`mark_changed`) would recompute every transitive
+//! dependent even when nothing it reads from actually changed value. The
+//! pull half, [`UpdateScheduler::propagate_pull`], closes that gap by
+//! comparing cached values (see [`Node::output`]) before recomputing, and
+//! cutting propagation as soon as a node's own output turns out unchanged -
+//! an Adapton-style change-propagation pass.
+//!
+//! # Weak Edges
+//!
+//! `add_edge` creates a strong edge: the dependent is kept dirty-tracked and
+//! counted in the dependency's in-degree. `add_weak_edge` creates a
+//! "read-if-present" edge instead - the dependent can still pull the weak
+//! dependency's current value once it's dirty for some other reason, but the
+//! dependency changing alone never marks it maybe-dirty, and the edge is
+//! invisible to `topological_sort`'s in-degree counts. This is how a would-be
+//! cycle gets broken: make one direction of it weak.
 
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
 use super::node::{Node, NodeId, DirtyState};
 
+/// Error returned by [`UpdateScheduler::topological_sort`] when the node set
+/// contains a dependency cycle.
+///
+/// Kahn's algorithm alone can't distinguish "this node just hasn't been
+/// reached yet" from "this node is stuck in a cycle" - both leave it with a
+/// non-zero in-degree forever. This carries every node still stuck that way
+/// (`cyclic_nodes`), plus one concrete cycle reconstructed by a DFS over just
+/// those nodes, so the error is actually actionable.
+#[derive(Debug, Clone)]
+pub struct ScheduleError {
+    /// Every node that never reached in-degree zero: the members of some
+    /// cycle in the given node set, plus any node downstream of one.
+    pub cyclic_nodes: HashSet<NodeId>,
+    /// One concrete cycle among `cyclic_nodes`, as a path of node IDs where
+    /// the first and last entries are the same node.
+    pub cycle: Vec<NodeId>,
+}
+
+impl fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "dependency cycle detected among {} node(s): {:?}",
+            self.cyclic_nodes.len(),
+            self.cycle
+        )
+    }
+}
+
+impl std::error::Error for ScheduleError {}
+
 /// The update scheduler manages the dependency graph and coordinates updates.
 pub struct UpdateScheduler {
     /// All nodes in the graph, indexed by ID.
@@ -44,7 +92,7 @@ impl UpdateScheduler {
 
     /// Remove a node from the graph.
     ///
-    /// Also removes all edges involving this node.
+    /// Also removes all edges (strong and weak) involving this node.
     pub fn remove_node(&mut self, node_id: NodeId) {
         if let Some(node) = self.nodes.remove(&node_id) {
             // Remove this node from its dependencies' dependent lists
@@ -60,6 +108,18 @@ impl UpdateScheduler {
                     dependent.remove_dependency(node_id);
                 }
             }
+
+            // Same cleanup for the weak edge sets.
+            for dep_id in node.weak_dependencies() {
+                if let Some(dep) = self.nodes.get_mut(dep_id) {
+                    dep.remove_weak_dependent(node_id);
+                }
+            }
+            for dependent_id in node.weak_dependents() {
+                if let Some(dependent) = self.nodes.get_mut(dependent_id) {
+                    dependent.remove_weak_dependency(node_id);
+                }
+            }
         }
     }
 
@@ -95,10 +155,39 @@ impl UpdateScheduler {
         }
     }
 
+    /// Add a weak dependency edge: `dependent` can read `dependency`'s value,
+    /// but `dependency` changing does not mark `dependent` maybe-dirty on its
+    /// own, and the edge is excluded from `topological_sort`'s in-degree
+    /// counts entirely.
+    ///
+    /// Useful for "read-if-present" relationships, and for breaking a would-be
+    /// cycle: a strong edge back the other way would make `topological_sort`
+    /// report a cycle, but a weak one doesn't participate in that count.
+    pub fn add_weak_edge(&mut self, dependency: NodeId, dependent: NodeId) {
+        if let Some(dep_node) = self.nodes.get_mut(&dependency) {
+            dep_node.add_weak_dependent(dependent);
+        }
+        if let Some(dependent_node) = self.nodes.get_mut(&dependent) {
+            dependent_node.add_weak_dependency(dependency);
+        }
+    }
+
+    /// Remove a weak dependency edge.
+    pub fn remove_weak_edge(&mut self, dependency: NodeId, dependent: NodeId) {
+        if let Some(dep_node) = self.nodes.get_mut(&dependency) {
+            dep_node.remove_weak_dependent(dependent);
+        }
+        if let Some(dependent_node) = self.nodes.get_mut(&dependent) {
+            dependent_node.remove_weak_dependency(dependency);
+        }
+    }
+
     /// Mark a source node as changed and propagate dirty flags.
     ///
-    /// Returns the set of node IDs that need to be processed.
-    pub fn mark_changed(&mut self, source_id: NodeId) -> Vec<NodeId> {
+    /// Returns the set of node IDs that need to be processed, in topological
+    /// order, or a [`ScheduleError`] if the propagated set contains a
+    /// dependency cycle.
+    pub fn mark_changed(&mut self, source_id: NodeId) -> Result<Vec<NodeId>, ScheduleError> {
         let mut to_process = Vec::new();
         let mut visited = HashSet::new();
         let mut queue = VecDeque::new();
@@ -135,10 +224,14 @@ impl UpdateScheduler {
         self.topological_sort(to_process)
     }
 
+
     /// Perform a topological sort of the given nodes.
     ///
-    /// Returns nodes in order such that dependencies come before dependents.
-    fn topological_sort(&self, nodes: Vec<NodeId>) -> Vec<NodeId> {
+    /// Returns nodes in order such that dependencies come before dependents,
+    /// or a [`ScheduleError`] if `nodes` contains a dependency cycle. Cycle
+    /// reconstruction only runs on that error path, so the common acyclic
+    /// case pays no extra cost per edge.
+    pub fn topological_sort(&self, nodes: Vec<NodeId>) -> Result<Vec<NodeId>, ScheduleError> {
         let node_set: HashSet<_> = nodes.iter().copied().collect();
         let mut in_degree: HashMap<NodeId, usize> = HashMap::new();
         let mut result = Vec::new();
@@ -175,7 +268,170 @@ impl UpdateScheduler {
             }
         }
 
-        result
+        if result.len() < nodes.len() {
+            // Anything left with a non-zero in-degree never reached the
+            // queue, which (per Kahn's algorithm) means it's in a cycle or
+            // downstream of one, rather than merely unreached.
+            let cyclic_nodes: HashSet<NodeId> = in_degree
+                .into_iter()
+                .filter(|&(_, degree)| degree > 0)
+                .map(|(id, _)| id)
+                .collect();
+            let cycle = self.find_cycle(&cyclic_nodes);
+            return Err(ScheduleError {
+                cyclic_nodes,
+                cycle,
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// Reconstruct one concrete cycle among `cyclic_nodes` via a DFS that
+    /// only follows `dependents()` edges into other `cyclic_nodes`.
+    ///
+    /// `cyclic_nodes` is assumed to be exactly the set of nodes left with a
+    /// non-zero in-degree by `topological_sort`'s Kahn's-algorithm pass:
+    /// every node in it is on a cycle or downstream of one, so at least one
+    /// cycle exists among them.
+    fn find_cycle(&self, cyclic_nodes: &HashSet<NodeId>) -> Vec<NodeId> {
+        let mut visited: HashSet<NodeId> = HashSet::new();
+        let mut path: Vec<NodeId> = Vec::new();
+
+        for &start in cyclic_nodes {
+            if !visited.contains(&start) {
+                if let Some(cycle) = self.find_cycle_from(start, cyclic_nodes, &mut visited, &mut path) {
+                    return cycle;
+                }
+            }
+        }
+
+        Vec::new()
+    }
+
+    fn find_cycle_from(
+        &self,
+        node_id: NodeId,
+        cyclic_nodes: &HashSet<NodeId>,
+        visited: &mut HashSet<NodeId>,
+        path: &mut Vec<NodeId>,
+    ) -> Option<Vec<NodeId>> {
+        if let Some(back_edge_pos) = path.iter().position(|&id| id == node_id) {
+            let mut cycle = path[back_edge_pos..].to_vec();
+            cycle.push(node_id);
+            return Some(cycle);
+        }
+
+        if visited.contains(&node_id) {
+            // Already explored with no cycle found from here; revisiting it
+            // from a different start would find the same dead end.
+            return None;
+        }
+
+        visited.insert(node_id);
+        path.push(node_id);
+
+        if let Some(node) = self.nodes.get(&node_id) {
+            for &dependent_id in node.dependents() {
+                if cyclic_nodes.contains(&dependent_id) {
+                    if let Some(cycle) =
+                        self.find_cycle_from(dependent_id, cyclic_nodes, visited, path)
+                    {
+                        return Some(cycle);
+                    }
+                }
+            }
+        }
+
+        path.pop();
+        None
+    }
+
+    /// The pull half of the scheduler's push-pull design: given the
+    /// topologically-ordered `to_process` list from [`mark_changed`](Self::mark_changed),
+    /// only actually recompute a node when a value genuinely flowed into it,
+    /// and only propagate further when its own output actually changed.
+    ///
+    /// For each node, in order:
+    ///
+    /// 1. Compare every dependency's current cached [`Node::output`] against
+    ///    the value this node last consumed from it
+    ///    ([`Node::last_consumed`]). If they're all byte-for-byte equal (and
+    ///    there's at least one dependency), nothing flowed in - mark the node
+    ///    clean and move on without calling `recompute` at all.
+    /// 2. Otherwise call `recompute(node_id)` to get the node's fresh output,
+    ///    record what was consumed from each dependency this pass, and cache
+    ///    the new output.
+    /// 3. Only if the new output differs from the previously cached one is
+    ///    `node_id` included in the returned list - the signal to whatever
+    ///    drove this call that *this* node's dependents should still expect a
+    ///    new value. A node recomputed to the same output isn't, which is
+    ///    what cuts propagation short of the full `to_process` set: that
+    ///    node's own dependents will see an unchanged dependency output at
+    ///    step 1 and stop there too.
+    ///
+    /// Because a node with no dependencies (a source) has nothing to compare,
+    /// it always recomputes when asked.
+    pub fn propagate_pull<F>(&mut self, to_process: Vec<NodeId>, mut recompute: F) -> Vec<NodeId>
+    where
+        F: FnMut(NodeId) -> Vec<u8>,
+    {
+        let mut actually_changed = Vec::new();
+
+        for node_id in to_process {
+            let dependencies: Vec<NodeId> = match self.nodes.get(&node_id) {
+                Some(node) => node.dependencies().iter().copied().collect(),
+                None => continue,
+            };
+
+            let anything_flowed_in = dependencies.is_empty()
+                || dependencies.iter().any(|dep_id| {
+                    let current = self.nodes.get(dep_id).and_then(Node::output);
+                    let last_seen = self
+                        .nodes
+                        .get(&node_id)
+                        .and_then(|node| node.last_consumed(*dep_id));
+                    current != last_seen
+                });
+
+            if !anything_flowed_in {
+                if let Some(node) = self.nodes.get_mut(&node_id) {
+                    node.mark_clean();
+                }
+                continue;
+            }
+
+            let new_output = recompute(node_id);
+
+            let dependency_values: Vec<(NodeId, Vec<u8>)> = dependencies
+                .iter()
+                .filter_map(|dep_id| {
+                    self.nodes
+                        .get(dep_id)
+                        .and_then(Node::output)
+                        .map(|value| (*dep_id, value.to_vec()))
+                })
+                .collect();
+
+            let output_changed = self
+                .nodes
+                .get(&node_id)
+                .map_or(true, |node| node.output() != Some(new_output.as_slice()));
+
+            if let Some(node) = self.nodes.get_mut(&node_id) {
+                for (dep_id, value) in dependency_values {
+                    node.record_consumed(dep_id, value);
+                }
+                node.set_output(new_output);
+                node.mark_clean();
+            }
+
+            if output_changed {
+                actually_changed.push(node_id);
+            }
+        }
+
+        actually_changed
     }
 
     /// Get the total number of nodes in the graph.
@@ -273,14 +529,190 @@ mod tests {
         scheduler.get_node_mut(derived2_id).unwrap().mark_clean();
 
         // Mark source as changed
-        let to_process = scheduler.mark_changed(source_id);
+        let to_process = scheduler.mark_changed(source_id).unwrap();
 
         // Both derived nodes should be marked
         assert_eq!(to_process.len(), 2);
-        
+
         // They should be in topological order (derived1 before derived2)
         let pos1 = to_process.iter().position(|&id| id == derived1_id);
         let pos2 = to_process.iter().position(|&id| id == derived2_id);
         assert!(pos1 < pos2);
     }
+
+    #[test]
+    fn topological_sort_is_ok_for_acyclic_graphs() {
+        let mut scheduler = UpdateScheduler::new();
+
+        let a = scheduler.add_node(Node::source());
+        let b = scheduler.add_node(Node::derived());
+        let c = scheduler.add_node(Node::derived());
+
+        scheduler.add_edge(a, b);
+        scheduler.add_edge(b, c);
+
+        let sorted = scheduler.topological_sort(vec![a, b, c]).unwrap();
+        assert_eq!(sorted, vec![a, b, c]);
+    }
+
+    #[test]
+    fn topological_sort_detects_a_direct_cycle() {
+        let mut scheduler = UpdateScheduler::new();
+
+        let a = scheduler.add_node(Node::derived());
+        let b = scheduler.add_node(Node::derived());
+
+        // a -> b -> a
+        scheduler.add_edge(a, b);
+        scheduler.add_edge(b, a);
+
+        let err = scheduler.topological_sort(vec![a, b]).unwrap_err();
+
+        assert_eq!(err.cyclic_nodes.len(), 2);
+        assert!(err.cyclic_nodes.contains(&a));
+        assert!(err.cyclic_nodes.contains(&b));
+
+        // The reconstructed cycle is a closed walk through both nodes.
+        assert_eq!(err.cycle.first(), err.cycle.last());
+        assert!(err.cycle.contains(&a));
+        assert!(err.cycle.contains(&b));
+    }
+
+    #[test]
+    fn weak_edges_break_what_would_otherwise_be_a_cycle() {
+        let mut scheduler = UpdateScheduler::new();
+
+        let a = scheduler.add_node(Node::derived());
+        let b = scheduler.add_node(Node::derived());
+
+        scheduler.add_edge(a, b);
+        // Without this being weak, a <-> b would be a cycle.
+        scheduler.add_weak_edge(b, a);
+
+        let sorted = scheduler.topological_sort(vec![a, b]).unwrap();
+        assert_eq!(sorted, vec![a, b]);
+
+        // The weak edge is still recorded, just not counted.
+        assert!(scheduler.get_node(a).unwrap().weak_dependencies().contains(&b));
+        assert!(scheduler.get_node(b).unwrap().weak_dependents().contains(&a));
+    }
+
+    #[test]
+    fn weak_edges_do_not_propagate_mark_changed() {
+        let mut scheduler = UpdateScheduler::new();
+
+        let source = scheduler.add_node(Node::source());
+        let weak_reader = scheduler.add_node(Node::derived());
+        let strong_reader = scheduler.add_node(Node::derived());
+
+        scheduler.add_weak_edge(source, weak_reader);
+        scheduler.add_edge(source, strong_reader);
+
+        scheduler.get_node_mut(weak_reader).unwrap().mark_clean();
+        scheduler.get_node_mut(strong_reader).unwrap().mark_clean();
+
+        let to_process = scheduler.mark_changed(source).unwrap();
+
+        // Only the strong dependent is propagated to.
+        assert_eq!(to_process, vec![strong_reader]);
+        assert!(scheduler.get_node(weak_reader).unwrap().is_clean());
+    }
+
+    #[test]
+    fn propagate_pull_recomputes_every_node_on_first_run() {
+        let mut scheduler = UpdateScheduler::new();
+
+        let source = scheduler.add_node(Node::source());
+        let derived = scheduler.add_node(Node::derived());
+
+        scheduler.add_edge(source, derived);
+        scheduler.get_node_mut(source).unwrap().set_output(vec![1]);
+
+        let to_process = scheduler.mark_changed(source).unwrap();
+        let changed = scheduler.propagate_pull(to_process, |_| vec![2]);
+
+        // No prior cached output to compare against, so it recomputes and
+        // reports a change.
+        assert_eq!(changed, vec![derived]);
+        assert_eq!(scheduler.get_node(derived).unwrap().output(), Some(&[2][..]));
+    }
+
+    #[test]
+    fn propagate_pull_cuts_propagation_when_output_is_unchanged() {
+        let mut scheduler = UpdateScheduler::new();
+
+        // source -> middle -> leaf
+        let source = scheduler.add_node(Node::source());
+        let middle = scheduler.add_node(Node::derived());
+        let leaf = scheduler.add_node(Node::derived());
+
+        scheduler.add_edge(source, middle);
+        scheduler.add_edge(middle, leaf);
+
+        // Prime the cache as if a prior run had already settled: source's
+        // output is consumed by middle, which computes to a fixed value that
+        // leaf has already consumed too.
+        scheduler.get_node_mut(source).unwrap().set_output(vec![1]);
+        scheduler.get_node_mut(middle).unwrap().set_output(vec![9]);
+        scheduler
+            .get_node_mut(middle)
+            .unwrap()
+            .record_consumed(source, vec![1]);
+        scheduler.get_node_mut(leaf).unwrap().set_output(vec![42]);
+        scheduler
+            .get_node_mut(leaf)
+            .unwrap()
+            .record_consumed(middle, vec![9]);
+
+        // source changes, but its recomputation (simulated by the caller)
+        // produces the exact same bytes as before.
+        scheduler.get_node_mut(source).unwrap().set_output(vec![1]);
+        scheduler.get_node_mut(middle).unwrap().mark_maybe_dirty();
+        scheduler.get_node_mut(leaf).unwrap().mark_maybe_dirty();
+
+        let to_process = vec![middle, leaf];
+        let changed = scheduler.propagate_pull(to_process, |node_id| {
+            if node_id == middle {
+                vec![9] // same as before - nothing genuinely changed
+            } else {
+                panic!("leaf should never be recomputed: middle's output didn't change")
+            }
+        });
+
+        assert!(changed.is_empty());
+        assert!(scheduler.get_node(middle).unwrap().is_clean());
+        assert!(scheduler.get_node(leaf).unwrap().is_clean());
+        // leaf's cached output is untouched, since it was never recomputed.
+        assert_eq!(scheduler.get_node(leaf).unwrap().output(), Some(&[42][..]));
+    }
+
+    #[test]
+    fn topological_sort_isolates_cycle_from_acyclic_nodes() {
+        let mut scheduler = UpdateScheduler::new();
+
+        // a -> b -> c -> b (b/c cycle), plus an unrelated acyclic d -> e.
+        let a = scheduler.add_node(Node::source());
+        let b = scheduler.add_node(Node::derived());
+        let c = scheduler.add_node(Node::derived());
+        let d = scheduler.add_node(Node::source());
+        let e = scheduler.add_node(Node::derived());
+
+        scheduler.add_edge(a, b);
+        scheduler.add_edge(b, c);
+        scheduler.add_edge(c, b);
+        scheduler.add_edge(d, e);
+
+        let err = scheduler
+            .topological_sort(vec![a, b, c, d, e])
+            .unwrap_err();
+
+        // Only the nodes actually stuck in the cycle are reported - `a`, `d`,
+        // and `e` all reach in-degree zero and complete normally.
+        assert_eq!(err.cyclic_nodes.len(), 2);
+        assert!(err.cyclic_nodes.contains(&b));
+        assert!(err.cyclic_nodes.contains(&c));
+        assert!(!err.cyclic_nodes.contains(&a));
+        assert!(!err.cyclic_nodes.contains(&d));
+        assert!(!err.cyclic_nodes.contains(&e));
+    }
 }
@@ -8,23 +8,43 @@
 //! - Wasmtime (WASM runtime)
 
 use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
 
 use cranelift::prelude::*;
+use cranelift_codegen::ir::FuncRef;
 use cranelift_jit::{JITBuilder, JITModule};
 use cranelift_module::{Module, Linkage, FuncId};
+use cranelift_object::{ObjectBuilder, ObjectModule};
 use target_lexicon::Triple;
 
+use super::intrinsics::lookup_intrinsic;
 use super::ir::{Op, OpCode, Operand, TraceIR};
+use super::optimizer::{self, OptLevel};
 
 /// Type alias for JIT-compiled functions: fn(*const f64) -> f64
 type JitFn = unsafe extern "C" fn(*const f64) -> f64;
 
+/// Type alias for the batched entry point: fn(inputs, outputs, n_rows).
+///
+/// `inputs` is row-major `[n_rows][num_inputs]`; `outputs` is `[n_rows]`.
+type JitFnBatch = unsafe extern "C" fn(*const f64, *mut f64, i64);
+
 /// A compiled function that can be called with f64 inputs
 pub struct CompiledFunction {
-    /// The JIT module (keeps code alive)
-    _module: JITModule,
+    /// The JIT module (keeps code alive). `JITModule` holds non-`Sync`
+    /// interior-mutable state (symbol table entries wrapped for `Send`, not
+    /// `Sync`), so it's never touched again after compilation finishes -
+    /// wrapped in a `Mutex` purely so `CompiledFunction`, and therefore
+    /// `Arc<CompiledFunction>`, is `Sync` and can cross the `#[pyclass]`
+    /// boundary and the worker-pool thread hop in `WorkerRegistry`.
+    _module: Mutex<JITModule>,
     /// Function pointer
     func_ptr: JitFn,
+    /// Batched entry point mapping the same kernel over many rows at once.
+    batch_func_ptr: JitFnBatch,
     /// Number of inputs
     pub num_inputs: usize,
 }
@@ -35,189 +55,553 @@ impl CompiledFunction {
         assert_eq!(inputs.len(), self.num_inputs, "Wrong number of inputs");
         unsafe { (self.func_ptr)(inputs.as_ptr()) }
     }
+
+    /// Map the compiled kernel over `n_rows` rows of a row-major input buffer
+    /// (`n_rows * num_inputs` elements), returning one output per row.
+    ///
+    /// The whole loop runs in generated code via a second Cranelift entry
+    /// point, so a batch of rows pays FFI overhead once instead of once per
+    /// row as a Python loop calling [`call`](Self::call) would.
+    pub fn call_batch(&self, inputs: &[f64], n_rows: usize) -> Vec<f64> {
+        assert_eq!(
+            inputs.len(),
+            n_rows * self.num_inputs,
+            "Expected {} inputs ({} rows of {}), got {}",
+            n_rows * self.num_inputs,
+            n_rows,
+            self.num_inputs,
+            inputs.len(),
+        );
+        let mut outputs = vec![0.0; n_rows];
+        unsafe {
+            (self.batch_func_ptr)(inputs.as_ptr(), outputs.as_mut_ptr(), n_rows as i64);
+        }
+        outputs
+    }
+}
+
+/// Cache hit/miss counters for [`JitCompiler::compile`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
 }
 
 /// JIT compiler using Cranelift
 pub struct JitCompiler {
     /// ISA for the current platform
     isa: isa::OwnedTargetIsa,
+    /// How aggressively `compile()` optimizes a trace before lowering it.
+    opt_level: OptLevel,
+    /// Content-addressed cache of previously compiled traces, keyed by a
+    /// stable hash of the canonicalized `TraceIR`. Compiling the same trace
+    /// twice (e.g. a hot loop calling the same traced kernel) should only
+    /// pay codegen cost once.
+    cache: RwLock<HashMap<u64, Arc<CompiledFunction>>>,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
 }
 
 impl JitCompiler {
-    /// Create a new JIT compiler for the host platform
+    /// Create a new JIT compiler for the host platform, with the default
+    /// (optimizing) opt level.
     pub fn new() -> Result<Self, String> {
+        Self::with_opt_level(OptLevel::default())
+    }
+
+    /// Create a new JIT compiler for the host platform at a specific opt level.
+    pub fn with_opt_level(opt_level: OptLevel) -> Result<Self, String> {
         let mut flag_builder = settings::builder();
         flag_builder.set("opt_level", "speed").map_err(|e| e.to_string())?;
-        
+
         let isa_builder = cranelift_native::builder()
             .map_err(|e| format!("Failed to create ISA builder: {}", e))?;
-        
+
         let flags = settings::Flags::new(flag_builder);
         let isa = isa_builder.finish(flags)
             .map_err(|e| format!("Failed to build ISA: {}", e))?;
-        
-        Ok(Self { isa })
+
+        Ok(Self {
+            isa,
+            opt_level,
+            cache: RwLock::new(HashMap::new()),
+            cache_hits: AtomicU64::new(0),
+            cache_misses: AtomicU64::new(0),
+        })
     }
-    
-    /// Compile a trace to native code
-    pub fn compile(&self, ir: &TraceIR) -> Result<CompiledFunction, String> {
+
+    /// Compile a trace to native code, JIT-ed for the host.
+    ///
+    /// Checks the content-addressed cache first; a trace that was already
+    /// compiled (same ops, inputs, and output) returns the cached function
+    /// without re-running codegen. See [`cache_stats`](Self::cache_stats).
+    pub fn compile(&self, ir: &TraceIR) -> Result<Arc<CompiledFunction>, String> {
+        let key = trace_hash(ir);
+
+        if let Some(cached) = self.cache.read().expect("cache lock poisoned").get(&key) {
+            self.cache_hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(Arc::clone(cached));
+        }
+
+        let compiled = Arc::new(self.compile_uncached(ir)?);
+
+        self.cache_misses.fetch_add(1, Ordering::Relaxed);
+        self.cache
+            .write()
+            .expect("cache lock poisoned")
+            .insert(key, Arc::clone(&compiled));
+
+        Ok(compiled)
+    }
+
+    /// Current cache hit/miss counts, for diagnosing how much a workload
+    /// benefits from trace reuse.
+    pub fn cache_stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.cache_hits.load(Ordering::Relaxed),
+            misses: self.cache_misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// The opt level this compiler was constructed with.
+    pub fn opt_level(&self) -> OptLevel {
+        self.opt_level
+    }
+
+    fn compile_uncached(&self, ir: &TraceIR) -> Result<CompiledFunction, String> {
+        let ir = optimizer::optimize(ir, self.opt_level);
+        let ir = &ir;
+
         // Create JIT module
-        let builder = JITBuilder::with_isa(
+        let mut builder = JITBuilder::with_isa(
             self.isa.clone(),
             cranelift_module::default_libcall_names(),
         );
-        let mut module = JITModule::new(builder);
-        
-        // Create function signature: fn(*i64) -> f64
-        let mut ctx = module.make_context();
-        let ptr_type = module.target_config().pointer_type();
-        
-        ctx.func.signature.params.push(AbiParam::new(ptr_type));
-        ctx.func.signature.returns.push(AbiParam::new(types::F64));
-        
-        // Declare the function
-        let func_id = module.declare_function(
-            "jit_fn",
-            Linkage::Local,
-            &ctx.func.signature,
-        ).map_err(|e| e.to_string())?;
-        
-        // Build the function
-        let mut builder_ctx = FunctionBuilderContext::new();
-        let mut builder = FunctionBuilder::new(&mut ctx.func, &mut builder_ctx);
-        
-        let entry_block = builder.create_block();
-        builder.append_block_params_for_function_params(entry_block);
-        builder.switch_to_block(entry_block);
-        builder.seal_block(entry_block);
-        
-        // Get input pointer
-        let input_ptr = builder.block_params(entry_block)[0];
-        
-        // Map value IDs to Cranelift values
-        let mut values: HashMap<usize, Value> = HashMap::new();
-        
-        // Process each operation
-        for op in &ir.ops {
-            let result = match op.op {
-                OpCode::Load => {
-                    // Load from input array
-                    let name = match &op.operands[0] {
-                        Operand::String(s) => s.clone(),
-                        _ => return Err("Load expects string operand".into()),
-                    };
-                    let idx = *ir.inputs.get(&name)
-                        .ok_or_else(|| format!("Unknown input: {}", name))?;
-                    
-                    // Calculate offset: (idx - 1) * 8 bytes
-                    let offset = ((idx - 1) * 8) as i32;
-                    builder.ins().load(types::F64, MemFlags::new(), input_ptr, offset)
-                }
-                
-                OpCode::Const => {
-                    let val = match &op.operands[0] {
-                        Operand::Float(f) => *f,
-                        Operand::Ref(r) => *r as f64,
-                        _ => return Err("Const expects numeric operand".into()),
-                    };
-                    builder.ins().f64const(val)
-                }
-                
-                OpCode::Add => {
-                    let lhs = self.get_operand(&op.operands[0], &values, &mut builder)?;
-                    let rhs = self.get_operand(&op.operands[1], &values, &mut builder)?;
-                    builder.ins().fadd(lhs, rhs)
-                }
-                
-                OpCode::Sub => {
-                    let lhs = self.get_operand(&op.operands[0], &values, &mut builder)?;
-                    let rhs = self.get_operand(&op.operands[1], &values, &mut builder)?;
-                    builder.ins().fsub(lhs, rhs)
-                }
-                
-                OpCode::Mul => {
-                    let lhs = self.get_operand(&op.operands[0], &values, &mut builder)?;
-                    let rhs = self.get_operand(&op.operands[1], &values, &mut builder)?;
-                    builder.ins().fmul(lhs, rhs)
-                }
-                
-                OpCode::Div => {
-                    let lhs = self.get_operand(&op.operands[0], &values, &mut builder)?;
-                    let rhs = self.get_operand(&op.operands[1], &values, &mut builder)?;
-                    builder.ins().fdiv(lhs, rhs)
-                }
-                
-                OpCode::Neg => {
-                    let val = self.get_operand(&op.operands[0], &values, &mut builder)?;
-                    builder.ins().fneg(val)
-                }
-                
-                // Comparison ops return 0.0 or 1.0
-                OpCode::Lt | OpCode::Le | OpCode::Gt | OpCode::Ge | OpCode::Eq | OpCode::Ne => {
-                    let lhs = self.get_operand(&op.operands[0], &values, &mut builder)?;
-                    let rhs = self.get_operand(&op.operands[1], &values, &mut builder)?;
-                    let cond = match op.op {
-                        OpCode::Lt => FloatCC::LessThan,
-                        OpCode::Le => FloatCC::LessThanOrEqual,
-                        OpCode::Gt => FloatCC::GreaterThan,
-                        OpCode::Ge => FloatCC::GreaterThanOrEqual,
-                        OpCode::Eq => FloatCC::Equal,
-                        OpCode::Ne => FloatCC::NotEqual,
-                        _ => unreachable!(),
-                    };
-                    let cmp = builder.ins().fcmp(cond, lhs, rhs);
-                    // Convert i8 bool to f64 (0.0 or 1.0)
-                    let int_val = builder.ins().uextend(types::I64, cmp);
-                    builder.ins().fcvt_from_uint(types::F64, int_val)
-                }
-                
-                OpCode::Mod | OpCode::Call => {
-                    return Err(format!("Unsupported opcode: {:?}", op.op));
-                }
-            };
-            
-            values.insert(op.result, result);
+
+        // Bind the host address of every intrinsic the trace calls, so the
+        // `Call` and `Mod` ops lowered below can be resolved without touching
+        // a linker.
+        for name in called_intrinsic_names(ir)? {
+            let intrinsic = lookup_intrinsic(&name)
+                .ok_or_else(|| format!("Unsupported call target: {}", name))?;
+            builder.symbol(intrinsic.symbol, intrinsic.host_ptr);
         }
-        
-        // Return the output value
-        let output = values.get(&ir.output)
-            .ok_or("Output value not found")?;
-        builder.ins().return_(&[*output]);
-        
-        builder.finalize();
-        
-        // Compile the function
-        module.define_function(func_id, &mut ctx)
-            .map_err(|e| e.to_string())?;
-        module.clear_context(&mut ctx);
+
+        let mut module = JITModule::new(builder);
+
+        let func_id = lower_trace(&mut module, ir, Linkage::Local)?;
+        let batch_func_id = lower_trace_batch(&mut module, ir, Linkage::Local)?;
+
         module.finalize_definitions()
             .map_err(|e| e.to_string())?;
-        
-        // Get the function pointer
+
+        // Get the function pointers
         let code_ptr = module.get_finalized_function(func_id);
         let func_ptr: JitFn = unsafe { std::mem::transmute(code_ptr) };
-        
+
+        let batch_code_ptr = module.get_finalized_function(batch_func_id);
+        let batch_func_ptr: JitFnBatch = unsafe { std::mem::transmute(batch_code_ptr) };
+
         Ok(CompiledFunction {
-            _module: module,
+            _module: Mutex::new(module),
             func_ptr,
+            batch_func_ptr,
             num_inputs: ir.num_inputs(),
         })
     }
-    
-    fn get_operand(
-        &self,
-        op: &Operand,
-        values: &HashMap<usize, Value>,
-        builder: &mut FunctionBuilder,
-    ) -> Result<Value, String> {
-        match op {
-            Operand::Ref(id) => values.get(id)
-                .copied()
-                .ok_or_else(|| format!("Value {} not found", id)),
-            Operand::Float(f) => Ok(builder.ins().f64const(*f)),
-            Operand::String(s) => Err(format!("Unexpected string operand: {}", s)),
+
+    /// Ahead-of-time compile a trace to a relocatable object file for `triple`.
+    ///
+    /// Unlike [`compile`](Self::compile), this does not run anything in-process:
+    /// it builds an ISA for an arbitrary target and returns the object file's
+    /// bytes (ELF/Mach-O/COFF, whichever the triple's platform uses), suitable
+    /// for writing to a `.o` file and linking into another program.
+    pub fn compile_object(ir: &TraceIR, triple: Triple) -> Result<Vec<u8>, String> {
+        let mut flag_builder = settings::builder();
+        flag_builder.set("opt_level", "speed").map_err(|e| e.to_string())?;
+        let flags = settings::Flags::new(flag_builder);
+
+        let isa = isa::lookup(triple)
+            .map_err(|e| format!("Unsupported target triple: {}", e))?
+            .finish(flags)
+            .map_err(|e| format!("Failed to build ISA: {}", e))?;
+
+        let builder = ObjectBuilder::new(
+            isa,
+            "lattice_jit_object",
+            cranelift_module::default_libcall_names(),
+        ).map_err(|e| e.to_string())?;
+        let mut module = ObjectModule::new(builder);
+
+        lower_trace(&mut module, ir, Linkage::Export)?;
+        lower_trace_batch(&mut module, ir, Linkage::Export)?;
+
+        let product = module.finish();
+        product.emit().map_err(|e| e.to_string())
+    }
+}
+
+/// Stable hash of a canonicalized `TraceIR`, used as the compilation cache key.
+///
+/// Hashes the inputs map (sorted by name for order-independence), the output
+/// id, and every op's fields. `f64` operands are hashed by bit pattern since
+/// `f64` doesn't implement `Hash`.
+fn trace_hash(ir: &TraceIR) -> u64 {
+    let mut hasher = DefaultHasher::new();
+
+    let mut inputs: Vec<(&String, &usize)> = ir.inputs.iter().collect();
+    inputs.sort_by_key(|(name, _)| name.as_str());
+    for (name, id) in inputs {
+        name.hash(&mut hasher);
+        id.hash(&mut hasher);
+    }
+
+    ir.output.hash(&mut hasher);
+
+    for op in &ir.ops {
+        op.op.hash(&mut hasher);
+        op.result.hash(&mut hasher);
+        op.dtype.hash(&mut hasher);
+        for operand in &op.operands {
+            match operand {
+                Operand::Ref(id) => {
+                    0u8.hash(&mut hasher);
+                    id.hash(&mut hasher);
+                }
+                Operand::Float(f) => {
+                    1u8.hash(&mut hasher);
+                    f.to_bits().hash(&mut hasher);
+                }
+                Operand::String(s) => {
+                    2u8.hash(&mut hasher);
+                    s.hash(&mut hasher);
+                }
+            }
         }
     }
+
+    hasher.finish()
+}
+
+/// Collect the distinct intrinsic names a trace invokes: the target of every
+/// `Call` op, plus `fmod` for any `Mod` op (which is lowered as an fmod call).
+fn called_intrinsic_names(ir: &TraceIR) -> Result<Vec<String>, String> {
+    let mut names = Vec::new();
+    for op in &ir.ops {
+        let name = match op.op {
+            OpCode::Call => match op.operands.first() {
+                Some(Operand::String(s)) => s.clone(),
+                _ => return Err("Call expects a string operand naming the function".into()),
+            },
+            OpCode::Mod => "fmod".to_string(),
+            _ => continue,
+        };
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    Ok(names)
+}
+
+/// Lower `ir` into a `jit_fn` function body on `module`, declared with `linkage`.
+///
+/// Shared between the in-process JIT path ([`JitCompiler::compile`]) and the
+/// ahead-of-time object path ([`JitCompiler::compile_object`]) so the two
+/// backends stay in lockstep as opcodes are added.
+fn lower_trace<M: Module>(module: &mut M, ir: &TraceIR, linkage: Linkage) -> Result<FuncId, String> {
+    // Create function signature: fn(*const f64) -> f64
+    let mut ctx = module.make_context();
+    let ptr_type = module.target_config().pointer_type();
+
+    ctx.func.signature.params.push(AbiParam::new(ptr_type));
+    ctx.func.signature.returns.push(AbiParam::new(types::F64));
+
+    // Declare the function
+    let func_id = module.declare_function(
+        "jit_fn",
+        linkage,
+        &ctx.func.signature,
+    ).map_err(|e| e.to_string())?;
+
+    // Build the function
+    let mut builder_ctx = FunctionBuilderContext::new();
+    let mut builder = FunctionBuilder::new(&mut ctx.func, &mut builder_ctx);
+
+    let entry_block = builder.create_block();
+    builder.append_block_params_for_function_params(entry_block);
+    builder.switch_to_block(entry_block);
+    builder.seal_block(entry_block);
+
+    // Get input pointer
+    let input_ptr = builder.block_params(entry_block)[0];
+
+    let output = lower_ops(module, &mut builder, ir, input_ptr)?;
+
+    builder.ins().return_(&[output]);
+
+    builder.finalize();
+
+    // Compile the function
+    module.define_function(func_id, &mut ctx)
+        .map_err(|e| e.to_string())?;
+    module.clear_context(&mut ctx);
+
+    Ok(func_id)
+}
+
+/// Lower `ir` into a `jit_fn_batch` function body on `module`: maps the same
+/// per-row kernel [`lower_trace`] emits over every row of a row-major input
+/// buffer, looping entirely in generated code.
+///
+/// Signature: `fn(inputs: *const f64 /* [n_rows][num_inputs] */, outputs: *mut f64 /* [n_rows] */, n_rows: i64)`.
+/// Wraps the per-row body in a Cranelift loop with a block-param induction
+/// variable: each iteration computes the row's base offset (`row *
+/// num_inputs * 8`), lowers the ops reading from that offset, and stores the
+/// result at `outputs[row]`, removing the per-row FFI overhead a Python loop
+/// calling [`CompiledFunction::call`] once per row would pay.
+fn lower_trace_batch<M: Module>(module: &mut M, ir: &TraceIR, linkage: Linkage) -> Result<FuncId, String> {
+    let mut ctx = module.make_context();
+    let ptr_type = module.target_config().pointer_type();
+
+    ctx.func.signature.params.push(AbiParam::new(ptr_type)); // inputs
+    ctx.func.signature.params.push(AbiParam::new(ptr_type)); // outputs
+    ctx.func.signature.params.push(AbiParam::new(types::I64)); // n_rows
+
+    let func_id = module.declare_function(
+        "jit_fn_batch",
+        linkage,
+        &ctx.func.signature,
+    ).map_err(|e| e.to_string())?;
+
+    let mut builder_ctx = FunctionBuilderContext::new();
+    let mut builder = FunctionBuilder::new(&mut ctx.func, &mut builder_ctx);
+
+    let entry_block = builder.create_block();
+    let loop_header = builder.create_block();
+    let loop_body = builder.create_block();
+    let exit_block = builder.create_block();
+
+    builder.append_block_params_for_function_params(entry_block);
+    builder.switch_to_block(entry_block);
+    builder.seal_block(entry_block);
+
+    let inputs_ptr = builder.block_params(entry_block)[0];
+    let outputs_ptr = builder.block_params(entry_block)[1];
+    let n_rows = builder.block_params(entry_block)[2];
+
+    let zero = builder.ins().iconst(types::I64, 0);
+    builder.ins().jump(loop_header, &[zero]);
+
+    builder.append_block_param(loop_header, types::I64);
+    builder.switch_to_block(loop_header);
+    let row = builder.block_params(loop_header)[0];
+    let done = builder.ins().icmp(IntCC::SignedGreaterThanOrEqual, row, n_rows);
+    builder.ins().brif(done, exit_block, &[], loop_body, &[]);
+    // `loop_header` is only ever jumped to from `entry_block` and the bottom
+    // of `loop_body`, both already emitted above, so it can be sealed here.
+    builder.seal_block(loop_header);
+
+    builder.switch_to_block(loop_body);
+    builder.seal_block(loop_body);
+
+    let row_bytes = (ir.num_inputs() as i64) * 8;
+    let row_offset = builder.ins().imul_imm(row, row_bytes);
+    let row_input_ptr = builder.ins().iadd(inputs_ptr, row_offset);
+
+    let output = lower_ops(module, &mut builder, ir, row_input_ptr)?;
+
+    let out_offset = builder.ins().imul_imm(row, 8);
+    let row_output_ptr = builder.ins().iadd(outputs_ptr, out_offset);
+    builder.ins().store(MemFlags::new(), output, row_output_ptr, 0);
+
+    let next_row = builder.ins().iadd_imm(row, 1);
+    builder.ins().jump(loop_header, &[next_row]);
+    // `loop_header` is only ever jumped to from `entry_block` and the bottom
+    // of `loop_body`, both emitted by now, so it can be sealed here.
+    builder.seal_block(loop_header);
+
+    builder.switch_to_block(exit_block);
+    builder.seal_block(exit_block);
+    builder.ins().return_(&[]);
+
+    builder.finalize();
+
+    module.define_function(func_id, &mut ctx)
+        .map_err(|e| e.to_string())?;
+    module.clear_context(&mut ctx);
+
+    Ok(func_id)
+}
+
+/// Lower every op in `ir` against a single row whose inputs live at
+/// `input_ptr`, returning the output value. Shared by the single-row
+/// (`jit_fn`) and batched (`jit_fn_batch`) entry points so both stay in
+/// lockstep as opcodes are added.
+fn lower_ops<M: Module>(
+    module: &mut M,
+    builder: &mut FunctionBuilder,
+    ir: &TraceIR,
+    input_ptr: Value,
+) -> Result<Value, String> {
+    // Map value IDs to Cranelift values
+    let mut values: HashMap<usize, Value> = HashMap::new();
+
+    // Imported intrinsic functions declared so far, keyed by name, so a
+    // function called more than once in a trace is only imported once.
+    let mut imported: HashMap<String, FuncRef> = HashMap::new();
+
+    // Process each operation
+    for op in &ir.ops {
+        let result = match op.op {
+            OpCode::Load => {
+                // Load from input array
+                let name = match &op.operands[0] {
+                    Operand::String(s) => s.clone(),
+                    _ => return Err("Load expects string operand".into()),
+                };
+                let idx = *ir.inputs.get(&name)
+                    .ok_or_else(|| format!("Unknown input: {}", name))?;
+
+                // Calculate offset: (idx - 1) * 8 bytes
+                let offset = ((idx - 1) * 8) as i32;
+                builder.ins().load(types::F64, MemFlags::new(), input_ptr, offset)
+            }
+
+            OpCode::Const => {
+                let val = match &op.operands[0] {
+                    Operand::Float(f) => *f,
+                    Operand::Ref(r) => *r as f64,
+                    _ => return Err("Const expects numeric operand".into()),
+                };
+                builder.ins().f64const(val)
+            }
+
+            OpCode::Add => {
+                let lhs = get_operand(&op.operands[0], &values, builder)?;
+                let rhs = get_operand(&op.operands[1], &values, builder)?;
+                builder.ins().fadd(lhs, rhs)
+            }
+
+            OpCode::Sub => {
+                let lhs = get_operand(&op.operands[0], &values, builder)?;
+                let rhs = get_operand(&op.operands[1], &values, builder)?;
+                builder.ins().fsub(lhs, rhs)
+            }
+
+            OpCode::Mul => {
+                let lhs = get_operand(&op.operands[0], &values, builder)?;
+                let rhs = get_operand(&op.operands[1], &values, builder)?;
+                builder.ins().fmul(lhs, rhs)
+            }
+
+            OpCode::Div => {
+                let lhs = get_operand(&op.operands[0], &values, builder)?;
+                let rhs = get_operand(&op.operands[1], &values, builder)?;
+                builder.ins().fdiv(lhs, rhs)
+            }
+
+            OpCode::Neg => {
+                let val = get_operand(&op.operands[0], &values, builder)?;
+                builder.ins().fneg(val)
+            }
+
+            // Comparison ops return 0.0 or 1.0
+            OpCode::Lt | OpCode::Le | OpCode::Gt | OpCode::Ge | OpCode::Eq | OpCode::Ne => {
+                let lhs = get_operand(&op.operands[0], &values, builder)?;
+                let rhs = get_operand(&op.operands[1], &values, builder)?;
+                let cond = match op.op {
+                    OpCode::Lt => FloatCC::LessThan,
+                    OpCode::Le => FloatCC::LessThanOrEqual,
+                    OpCode::Gt => FloatCC::GreaterThan,
+                    OpCode::Ge => FloatCC::GreaterThanOrEqual,
+                    OpCode::Eq => FloatCC::Equal,
+                    OpCode::Ne => FloatCC::NotEqual,
+                    _ => unreachable!(),
+                };
+                let cmp = builder.ins().fcmp(cond, lhs, rhs);
+                // Convert i8 bool to f64 (0.0 or 1.0)
+                let int_val = builder.ins().uextend(types::I64, cmp);
+                builder.ins().fcvt_from_uint(types::F64, int_val)
+            }
+
+            OpCode::Mod => {
+                // Lower as an fmod libcall rather than a native instruction,
+                // since Cranelift has no float-remainder opcode.
+                let lhs = get_operand(&op.operands[0], &values, builder)?;
+                let rhs = get_operand(&op.operands[1], &values, builder)?;
+                call_intrinsic(module, builder, &mut imported, "fmod", &[lhs, rhs])?
+            }
+
+            OpCode::Call => {
+                let name = match &op.operands[0] {
+                    Operand::String(s) => s.clone(),
+                    _ => return Err("Call expects a string operand naming the function".into()),
+                };
+                let args: Vec<Value> = op.operands[1..]
+                    .iter()
+                    .map(|operand| get_operand(operand, &values, builder))
+                    .collect::<Result<_, _>>()?;
+                call_intrinsic(module, builder, &mut imported, &name, &args)?
+            }
+        };
+
+        values.insert(op.result, result);
+    }
+
+    values.get(&ir.output).copied().ok_or_else(|| "Output value not found".into())
+}
+
+/// Declare (if needed) and emit a call to a named math intrinsic.
+fn call_intrinsic<M: Module>(
+    module: &mut M,
+    builder: &mut FunctionBuilder,
+    imported: &mut HashMap<String, FuncRef>,
+    name: &str,
+    args: &[Value],
+) -> Result<Value, String> {
+    let intrinsic = lookup_intrinsic(name)
+        .ok_or_else(|| format!("Unsupported call target: {}", name))?;
+
+    if args.len() != intrinsic.arity {
+        return Err(format!(
+            "{} expects {} argument(s), got {}",
+            name, intrinsic.arity, args.len()
+        ));
+    }
+
+    let func_ref = if let Some(func_ref) = imported.get(name) {
+        *func_ref
+    } else {
+        let mut signature = module.make_signature();
+        for _ in 0..intrinsic.arity {
+            signature.params.push(AbiParam::new(types::F64));
+        }
+        signature.returns.push(AbiParam::new(types::F64));
+
+        let func_id = module
+            .declare_function(intrinsic.symbol, Linkage::Import, &signature)
+            .map_err(|e| e.to_string())?;
+        let func_ref = module.declare_func_in_func(func_id, builder.func);
+        imported.insert(name.to_string(), func_ref);
+        func_ref
+    };
+
+    let call = builder.ins().call(func_ref, args);
+    Ok(builder.inst_results(call)[0])
+}
+
+fn get_operand(
+    op: &Operand,
+    values: &HashMap<usize, Value>,
+    builder: &mut FunctionBuilder,
+) -> Result<Value, String> {
+    match op {
+        Operand::Ref(id) => values.get(id)
+            .copied()
+            .ok_or_else(|| format!("Value {} not found", id)),
+        Operand::Float(f) => Ok(builder.ins().f64const(*f)),
+        Operand::String(s) => Err(format!("Unexpected string operand: {}", s)),
+    }
 }
 
 impl Default for JitCompiler {
@@ -270,4 +654,180 @@ mod tests {
         let result = func.call(&[5.0, 3.0]);
         assert_eq!(result, 16.0);  // (5 + 3) * 2 = 16
     }
+
+    #[test]
+    fn test_compile_object_for_host_triple() {
+        let ir = TraceIR::from_json(r#"{
+            "inputs": {"x": 1, "y": 2},
+            "output": 3,
+            "ops": [
+                {"op": "load", "result": 1, "operands": ["x"], "dtype": "f64"},
+                {"op": "load", "result": 2, "operands": ["y"], "dtype": "f64"},
+                {"op": "add", "result": 3, "operands": [1, 2], "dtype": "f64"}
+            ]
+        }"#).unwrap();
+
+        let object_bytes = JitCompiler::compile_object(&ir, Triple::host()).unwrap();
+
+        // A real object file, not an empty/placeholder buffer.
+        assert!(!object_bytes.is_empty());
+    }
+
+    #[test]
+    fn test_call_sqrt_intrinsic() {
+        let ir = TraceIR::from_json(r#"{
+            "inputs": {"x": 1},
+            "output": 2,
+            "ops": [
+                {"op": "load", "result": 1, "operands": ["x"], "dtype": "f64"},
+                {"op": "call", "result": 2, "operands": ["sqrt", 1], "dtype": "f64"}
+            ]
+        }"#).unwrap();
+
+        let compiler = JitCompiler::new().unwrap();
+        let func = compiler.compile(&ir).unwrap();
+
+        assert_eq!(func.call(&[16.0]), 4.0);
+    }
+
+    #[test]
+    fn test_mod_lowers_to_fmod() {
+        let ir = TraceIR::from_json(r#"{
+            "inputs": {"x": 1, "y": 2},
+            "output": 3,
+            "ops": [
+                {"op": "load", "result": 1, "operands": ["x"], "dtype": "f64"},
+                {"op": "load", "result": 2, "operands": ["y"], "dtype": "f64"},
+                {"op": "mod", "result": 3, "operands": [1, 2], "dtype": "f64"}
+            ]
+        }"#).unwrap();
+
+        let compiler = JitCompiler::new().unwrap();
+        let func = compiler.compile(&ir).unwrap();
+
+        assert_eq!(func.call(&[10.0, 3.0]), 1.0);
+    }
+
+    #[test]
+    fn test_call_unknown_function_errors() {
+        let ir = TraceIR::from_json(r#"{
+            "inputs": {"x": 1},
+            "output": 2,
+            "ops": [
+                {"op": "load", "result": 1, "operands": ["x"], "dtype": "f64"},
+                {"op": "call", "result": 2, "operands": ["frobnicate", 1], "dtype": "f64"}
+            ]
+        }"#).unwrap();
+
+        let compiler = JitCompiler::new().unwrap();
+        assert!(compiler.compile(&ir).is_err());
+    }
+
+    #[test]
+    fn test_opt_level_none_still_runs_correctly() {
+        // Redundant trace: the optimizer would collapse this to a single
+        // constant, but opt_level=None should still produce the right answer.
+        let ir = TraceIR::from_json(r#"{
+            "inputs": {},
+            "output": 3,
+            "ops": [
+                {"op": "const", "result": 1, "operands": [2.0], "dtype": "f64"},
+                {"op": "const", "result": 2, "operands": [3.0], "dtype": "f64"},
+                {"op": "add", "result": 3, "operands": [1, 2], "dtype": "f64"}
+            ]
+        }"#).unwrap();
+
+        let compiler = JitCompiler::with_opt_level(OptLevel::None).unwrap();
+        let func = compiler.compile(&ir).unwrap();
+
+        assert_eq!(func.call(&[]), 5.0);
+    }
+
+    #[test]
+    fn test_call_batch_maps_over_rows() {
+        // (x + y) * 2
+        let ir = TraceIR::from_json(r#"{
+            "inputs": {"x": 1, "y": 2},
+            "output": 4,
+            "ops": [
+                {"op": "load", "result": 1, "operands": ["x"], "dtype": "f64"},
+                {"op": "load", "result": 2, "operands": ["y"], "dtype": "f64"},
+                {"op": "add", "result": 3, "operands": [1, 2], "dtype": "f64"},
+                {"op": "const", "result": 5, "operands": [2.0], "dtype": "f64"},
+                {"op": "mul", "result": 4, "operands": [3, 5], "dtype": "f64"}
+            ]
+        }"#).unwrap();
+
+        let compiler = JitCompiler::new().unwrap();
+        let func = compiler.compile(&ir).unwrap();
+
+        // Row-major [3 rows][2 inputs]
+        let inputs = vec![5.0, 3.0, 1.0, 1.0, 0.0, 0.0];
+        let outputs = func.call_batch(&inputs, 3);
+
+        assert_eq!(outputs, vec![16.0, 4.0, 0.0]);
+    }
+
+    #[test]
+    fn test_call_batch_matches_call_per_row() {
+        let ir = TraceIR::from_json(r#"{
+            "inputs": {"x": 1},
+            "output": 2,
+            "ops": [
+                {"op": "load", "result": 1, "operands": ["x"], "dtype": "f64"},
+                {"op": "call", "result": 2, "operands": ["sqrt", 1], "dtype": "f64"}
+            ]
+        }"#).unwrap();
+
+        let compiler = JitCompiler::new().unwrap();
+        let func = compiler.compile(&ir).unwrap();
+
+        let inputs = vec![4.0, 9.0, 16.0, 25.0];
+        let batch_outputs = func.call_batch(&inputs, 4);
+        let per_row_outputs: Vec<f64> = inputs.iter().map(|&x| func.call(&[x])).collect();
+
+        assert_eq!(batch_outputs, per_row_outputs);
+    }
+
+    #[test]
+    fn test_call_batch_zero_rows() {
+        let ir = TraceIR::from_json(r#"{
+            "inputs": {"x": 1},
+            "output": 1,
+            "ops": [
+                {"op": "load", "result": 1, "operands": ["x"], "dtype": "f64"}
+            ]
+        }"#).unwrap();
+
+        let compiler = JitCompiler::new().unwrap();
+        let func = compiler.compile(&ir).unwrap();
+
+        assert_eq!(func.call_batch(&[], 0), Vec::<f64>::new());
+    }
+
+    #[test]
+    fn test_compile_caches_identical_traces() {
+        let ir = TraceIR::from_json(r#"{
+            "inputs": {"x": 1, "y": 2},
+            "output": 3,
+            "ops": [
+                {"op": "load", "result": 1, "operands": ["x"], "dtype": "f64"},
+                {"op": "load", "result": 2, "operands": ["y"], "dtype": "f64"},
+                {"op": "add", "result": 3, "operands": [1, 2], "dtype": "f64"}
+            ]
+        }"#).unwrap();
+
+        let compiler = JitCompiler::new().unwrap();
+
+        let first = compiler.compile(&ir).unwrap();
+        assert_eq!(compiler.cache_stats().misses, 1);
+        assert_eq!(compiler.cache_stats().hits, 0);
+
+        let second = compiler.compile(&ir).unwrap();
+        assert_eq!(compiler.cache_stats().misses, 1);
+        assert_eq!(compiler.cache_stats().hits, 1);
+
+        // Same underlying compiled function, not a fresh compilation.
+        assert!(Arc::ptr_eq(&first, &second));
+    }
 }
@@ -0,0 +1,83 @@
+//! Transcendental Math Intrinsics
+//!
+//! Registry of the math functions `OpCode::Call` can invoke. Each entry
+//! carries the arity (so codegen can validate operand counts), the libm
+//! symbol name the AOT object backend imports and expects the final linker
+//! to resolve, and a host-callable function pointer the JIT backend binds
+//! directly via `JITBuilder::symbol` so it never has to touch a linker.
+
+/// A callable transcendental intrinsic.
+#[derive(Debug, Clone, Copy)]
+pub struct Intrinsic {
+    /// Number of f64 arguments the function takes (1 or 2).
+    pub arity: usize,
+    /// The libm symbol name to import for ahead-of-time linking.
+    pub symbol: &'static str,
+    /// Host-callable address, used to bind the symbol for in-process JIT.
+    pub host_ptr: *const u8,
+}
+
+// Safety: these are plain function pointers to `extern "C"` wrappers around
+// libm-equivalent std functions; they contain no thread-unsafe state.
+unsafe impl Send for Intrinsic {}
+unsafe impl Sync for Intrinsic {}
+
+unsafe extern "C" fn intrinsic_sin(x: f64) -> f64 {
+    x.sin()
+}
+
+unsafe extern "C" fn intrinsic_cos(x: f64) -> f64 {
+    x.cos()
+}
+
+unsafe extern "C" fn intrinsic_exp(x: f64) -> f64 {
+    x.exp()
+}
+
+unsafe extern "C" fn intrinsic_log(x: f64) -> f64 {
+    x.ln()
+}
+
+unsafe extern "C" fn intrinsic_sqrt(x: f64) -> f64 {
+    x.sqrt()
+}
+
+unsafe extern "C" fn intrinsic_pow(x: f64, y: f64) -> f64 {
+    x.powf(y)
+}
+
+unsafe extern "C" fn intrinsic_fmod(x: f64, y: f64) -> f64 {
+    x % y
+}
+
+/// Look up a named intrinsic by the name used in the trace's `Call` op.
+pub fn lookup_intrinsic(name: &str) -> Option<Intrinsic> {
+    let (symbol, arity, host_ptr): (&'static str, usize, *const u8) = match name {
+        "sin" => ("sin", 1, intrinsic_sin as *const u8),
+        "cos" => ("cos", 1, intrinsic_cos as *const u8),
+        "exp" => ("exp", 1, intrinsic_exp as *const u8),
+        "log" => ("log", 1, intrinsic_log as *const u8),
+        "sqrt" => ("sqrt", 1, intrinsic_sqrt as *const u8),
+        "pow" => ("pow", 2, intrinsic_pow as *const u8),
+        "fmod" => ("fmod", 2, intrinsic_fmod as *const u8),
+        _ => return None,
+    };
+
+    Some(Intrinsic { arity, symbol, host_ptr })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_intrinsics_resolve() {
+        assert_eq!(lookup_intrinsic("sin").unwrap().arity, 1);
+        assert_eq!(lookup_intrinsic("pow").unwrap().arity, 2);
+    }
+
+    #[test]
+    fn unknown_intrinsic_is_none() {
+        assert!(lookup_intrinsic("frobnicate").is_none());
+    }
+}
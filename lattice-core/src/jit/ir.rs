@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Operation codes matching the Python tracer
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum OpCode {
     // Arithmetic
@@ -87,6 +87,17 @@ impl TraceIR {
     pub fn num_inputs(&self) -> usize {
         self.inputs.len()
     }
+
+    /// Run the standard optimization pipeline (constant folding, CSE, and
+    /// dead-code elimination to a fixpoint - see `jit::optimizer`) and return
+    /// the resulting, compacted `TraceIR`.
+    ///
+    /// This is what `JitCompiler` runs internally before codegen; exposed
+    /// directly on `TraceIR` for callers (and tests) that want the optimized
+    /// IR itself rather than a compiled function.
+    pub fn optimize(&self) -> TraceIR {
+        super::optimizer::optimize(self, super::optimizer::OptLevel::Basic)
+    }
 }
 
 #[cfg(test)]
@@ -110,4 +121,36 @@ mod tests {
         assert_eq!(ir.output, 3);
         assert_eq!(ir.ops.len(), 3);
     }
+
+    #[test]
+    fn optimize_shrinks_op_count_while_preserving_output_value() {
+        // (2 + 3) computed twice feeding a mul, plus an unreferenced dead
+        // const - folding collapses the arithmetic to literals, CSE dedupes
+        // the duplicate add, and DCE drops the dead op, leaving one `Const`.
+        let json = r#"{
+            "inputs": {},
+            "output": 5,
+            "ops": [
+                {"op": "const", "result": 1, "operands": [2.0], "dtype": "f64"},
+                {"op": "const", "result": 2, "operands": [3.0], "dtype": "f64"},
+                {"op": "add", "result": 3, "operands": [1, 2], "dtype": "f64"},
+                {"op": "add", "result": 4, "operands": [1, 2], "dtype": "f64"},
+                {"op": "mul", "result": 5, "operands": [3, 4], "dtype": "f64"},
+                {"op": "const", "result": 6, "operands": [42.0], "dtype": "f64"}
+            ]
+        }"#;
+        let ir = TraceIR::from_json(json).unwrap();
+
+        let optimized = ir.optimize();
+
+        assert!(optimized.ops.len() < ir.ops.len());
+        assert_eq!(optimized.ops.len(), 1);
+        let output_op = optimized
+            .ops
+            .iter()
+            .find(|op| op.result == optimized.output)
+            .unwrap();
+        assert_eq!(output_op.op, OpCode::Const);
+        assert!(matches!(output_op.operands[0], Operand::Float(f) if f == 25.0));
+    }
 }
@@ -12,12 +12,22 @@
 
 mod ir;
 mod codegen;
+mod intrinsics;
+mod optimizer;
+mod registry;
 
 pub use ir::{Op, OpCode, TraceIR};
 pub use codegen::{JitCompiler, CompiledFunction};
+pub use intrinsics::{Intrinsic, lookup_intrinsic};
+pub use optimizer::OptLevel;
+pub use registry::WorkerRegistry;
+
+use std::sync::Arc;
 
 use pyo3::prelude::*;
 use pyo3::exceptions::PyRuntimeError;
+use pyo3::types::PyBytes;
+use target_lexicon::Triple;
 
 /// Python-exposed JIT compiler.
 #[pyclass(name = "JitCompiler")]
@@ -28,9 +38,17 @@ pub struct PyJitCompiler {
 #[pymethods]
 impl PyJitCompiler {
     /// Create a new JIT compiler.
+    ///
+    /// `opt_level` mirrors AOT compiler `-O` flags: `0` runs the trace
+    /// exactly as traced, anything else runs the fixpoint optimizer pipeline
+    /// (constant folding, CSE, dead-code elimination). Defaults to optimized.
     #[new]
-    fn new() -> PyResult<Self> {
-        let compiler = JitCompiler::new()
+    fn new(opt_level: Option<u8>) -> PyResult<Self> {
+        let opt_level = match opt_level {
+            Some(0) => OptLevel::None,
+            _ => OptLevel::Basic,
+        };
+        let compiler = JitCompiler::with_opt_level(opt_level)
             .map_err(|e| PyRuntimeError::new_err(e))?;
         Ok(Self { compiler })
     }
@@ -54,7 +72,25 @@ impl PyJitCompiler {
         
         Ok(func.call(&inputs))
     }
-    
+
+    /// Ahead-of-time compile IR to a relocatable object file for `triple_str`
+    /// (e.g. `"x86_64-unknown-linux-gnu"`, `"aarch64-unknown-none"`).
+    ///
+    /// Returns the object file's bytes; the caller is responsible for writing
+    /// them to a `.o` file and linking them into the target program.
+    fn compile_to_object(&self, ir_json: &str, triple_str: &str, py: Python<'_>) -> PyResult<PyObject> {
+        let ir = TraceIR::from_json(ir_json)
+            .map_err(|e| PyRuntimeError::new_err(format!("IR parse error: {}", e)))?;
+
+        let triple: Triple = triple_str.parse()
+            .map_err(|e| PyRuntimeError::new_err(format!("Invalid target triple: {}", e)))?;
+
+        let object_bytes = JitCompiler::compile_object(&ir, triple)
+            .map_err(|e| PyRuntimeError::new_err(format!("Compile error: {}", e)))?;
+
+        Ok(PyBytes::new_bound(py, &object_bytes).into())
+    }
+
     /// Compile IR and benchmark execution.
     /// Returns (result, time_microseconds).
     fn benchmark(&self, ir_json: &str, inputs: Vec<f64>, iterations: usize) -> PyResult<(f64, f64)> {
@@ -77,6 +113,61 @@ impl PyJitCompiler {
         let total_us = elapsed.as_micros() as f64;
         Ok((result, total_us))
     }
+
+    /// Compile `ir_json`, reusing a cached compilation if this exact trace
+    /// was compiled before, and return a reusable handle.
+    ///
+    /// Unlike `compile_and_run`, the returned handle can be called repeatedly
+    /// without re-parsing or re-compiling the IR, so a hot loop over the same
+    /// traced kernel pays compilation cost at most once.
+    fn compile_cached(&self, ir_json: &str) -> PyResult<PyCompiledFunction> {
+        let ir = TraceIR::from_json(ir_json)
+            .map_err(|e| PyRuntimeError::new_err(format!("IR parse error: {}", e)))?;
+
+        let before = self.compiler.cache_stats();
+        let func = self.compiler.compile(&ir)
+            .map_err(|e| PyRuntimeError::new_err(format!("Compile error: {}", e)))?;
+        let after = self.compiler.cache_stats();
+
+        Ok(PyCompiledFunction {
+            func,
+            cache_hit: after.hits > before.hits,
+        })
+    }
+
+    /// Current (hits, misses) for the compilation cache.
+    fn cache_stats(&self) -> (u64, u64) {
+        let stats = self.compiler.cache_stats();
+        (stats.hits, stats.misses)
+    }
+
+    /// Compile a batch of traces across a thread pool, releasing the GIL for
+    /// the duration of compilation.
+    ///
+    /// Returns one entry per input trace, in order: a `CompiledFunction` on
+    /// success, or the compile error message (a `str`) on failure for that
+    /// trace alone.
+    fn compile_batch(&self, ir_jsons: Vec<String>, py: Python<'_>) -> PyResult<Vec<PyObject>> {
+        let mut traces = Vec::with_capacity(ir_jsons.len());
+        for ir_json in &ir_jsons {
+            let ir = TraceIR::from_json(ir_json)
+                .map_err(|e| PyRuntimeError::new_err(format!("IR parse error: {}", e)))?;
+            traces.push(ir);
+        }
+
+        let registry = WorkerRegistry::new().with_opt_level(self.compiler.opt_level());
+        let results = py.allow_threads(|| registry.compile_batch(traces));
+
+        let mut out = Vec::with_capacity(results.len());
+        for result in results {
+            let obj: PyObject = match result {
+                Ok(func) => Py::new(py, PyCompiledFunction { func, cache_hit: false })?.into_py(py),
+                Err(e) => e.into_py(py),
+            };
+            out.push(obj);
+        }
+        Ok(out)
+    }
 }
 
 impl CompiledFunction {
@@ -84,3 +175,46 @@ impl CompiledFunction {
         self.num_inputs
     }
 }
+
+/// A reusable handle to a compiled trace, returned by
+/// [`PyJitCompiler::compile_cached`].
+#[pyclass(name = "CompiledFunction")]
+pub struct PyCompiledFunction {
+    func: Arc<CompiledFunction>,
+    /// Whether this handle was served from the compilation cache rather than
+    /// freshly compiled.
+    #[pyo3(get)]
+    cache_hit: bool,
+}
+
+#[pymethods]
+impl PyCompiledFunction {
+    fn call(&self, inputs: Vec<f64>) -> PyResult<f64> {
+        if inputs.len() != self.func.num_inputs() {
+            return Err(PyRuntimeError::new_err(format!(
+                "Expected {} inputs, got {}", self.func.num_inputs(), inputs.len()
+            )));
+        }
+        Ok(self.func.call(&inputs))
+    }
+
+    /// Map the compiled kernel over `n_rows` rows of row-major `inputs`
+    /// (`n_rows * num_inputs` elements), returning one output per row.
+    ///
+    /// Runs the whole batch through a single Cranelift entry point instead
+    /// of looping over `call` in Python, avoiding per-row FFI overhead.
+    fn map(&self, inputs: Vec<f64>, n_rows: usize) -> PyResult<Vec<f64>> {
+        if inputs.len() != n_rows * self.func.num_inputs() {
+            return Err(PyRuntimeError::new_err(format!(
+                "Expected {} inputs ({} rows of {}), got {}",
+                n_rows * self.func.num_inputs(), n_rows, self.func.num_inputs(), inputs.len()
+            )));
+        }
+        Ok(self.func.call_batch(&inputs, n_rows))
+    }
+
+    #[getter]
+    fn num_inputs(&self) -> usize {
+        self.func.num_inputs()
+    }
+}
@@ -0,0 +1,380 @@
+//! IR Optimization Passes
+//!
+//! Rewrites a `TraceIR` before it reaches [`JitCompiler::compile`](super::JitCompiler::compile),
+//! shrinking the generated code and speeding up both compilation and execution
+//! for traces with redundant or dead computation.
+//!
+//! # Passes
+//!
+//! 1. **Constant folding** — arithmetic/comparison ops whose operands are all
+//!    literals (or fold to known constants) are evaluated at compile time and
+//!    replaced with a `Const`.
+//! 2. **Common-subexpression elimination** — ops with the same opcode and the
+//!    same (canonicalized) operands are deduplicated; later references are
+//!    redirected to the first occurrence's value id.
+//! 3. **Dead-code elimination** — starting from `ir.output`, ops whose results
+//!    are never (transitively) referenced are dropped.
+//!
+//! The passes are run to a fixpoint, since folding can expose new common
+//! subexpressions and CSE can expose new dead code.
+//!
+//! [`TraceIR::optimize`](super::ir::TraceIR::optimize) is the entry point
+//! most callers want; it just runs this pipeline at [`OptLevel::Basic`].
+
+use std::collections::{HashMap, HashSet};
+
+use super::ir::{Op, OpCode, Operand, TraceIR};
+
+/// How aggressively to optimize a trace before lowering it.
+///
+/// Mirrors the way AOT compilers expose optimization levels (e.g. `-O0`/`-O2`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptLevel {
+    /// Run the trace exactly as traced; useful for debugging generated code.
+    None,
+    /// Run constant folding, CSE, and dead-code elimination to a fixpoint.
+    Basic,
+}
+
+impl Default for OptLevel {
+    fn default() -> Self {
+        OptLevel::Basic
+    }
+}
+
+/// Maximum number of fixpoint iterations before giving up and returning the
+/// best result found so far. Real traces converge in 1-2 iterations; this is
+/// just a backstop against a pass pipeline bug causing an infinite loop.
+const MAX_ITERATIONS: usize = 8;
+
+/// Run the optimization pipeline over `ir` at the given `opt_level`.
+pub fn optimize(ir: &TraceIR, opt_level: OptLevel) -> TraceIR {
+    if opt_level == OptLevel::None {
+        return ir.clone();
+    }
+
+    let mut current = ir.clone();
+    for _ in 0..MAX_ITERATIONS {
+        let folded = constant_fold(&current);
+        let deduped = eliminate_common_subexpressions(&folded);
+        let pruned = eliminate_dead_code(&deduped);
+
+        if ops_unchanged(&current, &pruned) {
+            return pruned;
+        }
+        current = pruned;
+    }
+    current
+}
+
+fn ops_unchanged(a: &TraceIR, b: &TraceIR) -> bool {
+    a.output == b.output
+        && a.ops.len() == b.ops.len()
+        && a.ops.iter().zip(&b.ops).all(|(x, y)| {
+            x.result == y.result && x.op == y.op && operands_equal(&x.operands, &y.operands)
+        })
+}
+
+fn operands_equal(a: &[Operand], b: &[Operand]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(x, y)| OperandKey::from(x) == OperandKey::from(y))
+}
+
+/// Fold arithmetic/comparison ops whose operands are all known constants.
+fn constant_fold(ir: &TraceIR) -> TraceIR {
+    let mut known: HashMap<usize, f64> = HashMap::new();
+    let mut new_ops = Vec::with_capacity(ir.ops.len());
+
+    for op in &ir.ops {
+        if op.op == OpCode::Const {
+            if let Some(Operand::Float(f)) = op.operands.first() {
+                known.insert(op.result, *f);
+            }
+            new_ops.push(op.clone());
+            continue;
+        }
+
+        let operand_values: Option<Vec<f64>> = op
+            .operands
+            .iter()
+            .map(|operand| match operand {
+                Operand::Float(f) => Some(*f),
+                Operand::Ref(id) => known.get(id).copied(),
+                Operand::String(_) => None,
+            })
+            .collect();
+
+        let folded = operand_values.and_then(|values| eval_const(op.op, &values));
+
+        match folded {
+            Some(value) => {
+                known.insert(op.result, value);
+                new_ops.push(Op {
+                    op: OpCode::Const,
+                    result: op.result,
+                    operands: vec![Operand::Float(value)],
+                    dtype: op.dtype.clone(),
+                });
+            }
+            None => new_ops.push(op.clone()),
+        }
+    }
+
+    TraceIR {
+        inputs: ir.inputs.clone(),
+        output: ir.output,
+        ops: new_ops,
+    }
+}
+
+/// Evaluate an arithmetic/comparison opcode over literal operands, if it's
+/// one of the opcodes constant folding understands.
+fn eval_const(op: OpCode, values: &[f64]) -> Option<f64> {
+    match op {
+        OpCode::Add => Some(values[0] + values[1]),
+        OpCode::Sub => Some(values[0] - values[1]),
+        OpCode::Mul => Some(values[0] * values[1]),
+        OpCode::Div => Some(values[0] / values[1]),
+        OpCode::Mod => Some(values[0] % values[1]),
+        OpCode::Neg => Some(-values[0]),
+        OpCode::Lt => Some(bool_to_f64(values[0] < values[1])),
+        OpCode::Le => Some(bool_to_f64(values[0] <= values[1])),
+        OpCode::Gt => Some(bool_to_f64(values[0] > values[1])),
+        OpCode::Ge => Some(bool_to_f64(values[0] >= values[1])),
+        OpCode::Eq => Some(bool_to_f64(values[0] == values[1])),
+        OpCode::Ne => Some(bool_to_f64(values[0] != values[1])),
+        // Load/Const/Call have no constant semantics here: Load depends on
+        // runtime inputs, Const is already folded, and Call is treated as
+        // opaque (it may be an intrinsic we don't want to evaluate at
+        // compile time, e.g. for parity with the runtime libm).
+        OpCode::Const | OpCode::Load | OpCode::Call => None,
+    }
+}
+
+fn bool_to_f64(b: bool) -> f64 {
+    if b {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+/// A hashable, canonicalized view of an `Operand` used as a CSE key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum OperandKey {
+    Ref(usize),
+    Float(u64),
+    String(String),
+}
+
+impl From<&Operand> for OperandKey {
+    fn from(operand: &Operand) -> Self {
+        match operand {
+            Operand::Ref(id) => OperandKey::Ref(*id),
+            Operand::Float(f) => OperandKey::Float(f.to_bits()),
+            Operand::String(s) => OperandKey::String(s.clone()),
+        }
+    }
+}
+
+/// Deduplicate ops with identical opcode, (canonicalized) operands, *and*
+/// dtype - two ops that only differ in dtype aren't the same value, so they
+/// must not be folded into one another.
+fn eliminate_common_subexpressions(ir: &TraceIR) -> TraceIR {
+    let mut seen: HashMap<(OpCode, Vec<OperandKey>, String), usize> = HashMap::new();
+    let mut redirect: HashMap<usize, usize> = HashMap::new();
+    let mut new_ops = Vec::with_capacity(ir.ops.len());
+
+    for op in &ir.ops {
+        let canonical_operands: Vec<Operand> = op
+            .operands
+            .iter()
+            .map(|operand| match operand {
+                Operand::Ref(id) => Operand::Ref(*redirect.get(id).unwrap_or(id)),
+                other => other.clone(),
+            })
+            .collect();
+
+        // Calls are treated as opaque: nothing here guarantees a named
+        // intrinsic is pure across compiler versions, so never dedupe them.
+        if op.op == OpCode::Call {
+            new_ops.push(Op {
+                operands: canonical_operands,
+                ..op.clone()
+            });
+            continue;
+        }
+
+        let key = (
+            op.op,
+            canonical_operands.iter().map(OperandKey::from).collect(),
+            op.dtype.clone(),
+        );
+
+        if let Some(&existing_result) = seen.get(&key) {
+            redirect.insert(op.result, existing_result);
+            continue;
+        }
+
+        seen.insert(key, op.result);
+        new_ops.push(Op {
+            operands: canonical_operands,
+            ..op.clone()
+        });
+    }
+
+    let output = *redirect.get(&ir.output).unwrap_or(&ir.output);
+
+    TraceIR {
+        inputs: ir.inputs.clone(),
+        output,
+        ops: new_ops,
+    }
+}
+
+/// Drop ops whose results are never transitively referenced from `ir.output`.
+fn eliminate_dead_code(ir: &TraceIR) -> TraceIR {
+    let by_result: HashMap<usize, &Op> = ir.ops.iter().map(|op| (op.result, op)).collect();
+
+    let mut reachable = HashSet::new();
+    let mut worklist = vec![ir.output];
+
+    while let Some(id) = worklist.pop() {
+        if !reachable.insert(id) {
+            continue;
+        }
+        if let Some(op) = by_result.get(&id) {
+            for operand in &op.operands {
+                if let Operand::Ref(r) = operand {
+                    worklist.push(*r);
+                }
+            }
+        }
+    }
+
+    let new_ops: Vec<Op> = ir
+        .ops
+        .iter()
+        .filter(|op| reachable.contains(&op.result))
+        .cloned()
+        .collect();
+
+    TraceIR {
+        inputs: ir.inputs.clone(),
+        output: ir.output,
+        ops: new_ops,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ir_from(json: &str) -> TraceIR {
+        TraceIR::from_json(json).unwrap()
+    }
+
+    #[test]
+    fn constant_folding_collapses_literal_arithmetic() {
+        let ir = ir_from(
+            r#"{
+            "inputs": {},
+            "output": 3,
+            "ops": [
+                {"op": "const", "result": 1, "operands": [2.0], "dtype": "f64"},
+                {"op": "const", "result": 2, "operands": [3.0], "dtype": "f64"},
+                {"op": "add", "result": 3, "operands": [1, 2], "dtype": "f64"}
+            ]
+        }"#,
+        );
+
+        let optimized = optimize(&ir, OptLevel::Basic);
+        let output_op = optimized.ops.iter().find(|op| op.result == optimized.output).unwrap();
+
+        assert_eq!(output_op.op, OpCode::Const);
+        assert!(matches!(output_op.operands[0], Operand::Float(f) if f == 5.0));
+    }
+
+    #[test]
+    fn cse_redirects_duplicate_ops() {
+        let ir = ir_from(
+            r#"{
+            "inputs": {"x": 1, "y": 2},
+            "output": 5,
+            "ops": [
+                {"op": "load", "result": 1, "operands": ["x"], "dtype": "f64"},
+                {"op": "load", "result": 2, "operands": ["y"], "dtype": "f64"},
+                {"op": "add", "result": 3, "operands": [1, 2], "dtype": "f64"},
+                {"op": "add", "result": 4, "operands": [1, 2], "dtype": "f64"},
+                {"op": "mul", "result": 5, "operands": [3, 4], "dtype": "f64"}
+            ]
+        }"#,
+        );
+
+        let optimized = optimize(&ir, OptLevel::Basic);
+
+        // Only one `add` should remain; the duplicate is redirected.
+        let add_count = optimized.ops.iter().filter(|op| op.op == OpCode::Add).count();
+        assert_eq!(add_count, 1);
+    }
+
+    #[test]
+    fn dce_drops_unreferenced_ops() {
+        let ir = ir_from(
+            r#"{
+            "inputs": {"x": 1},
+            "output": 2,
+            "ops": [
+                {"op": "load", "result": 1, "operands": ["x"], "dtype": "f64"},
+                {"op": "const", "result": 2, "operands": [1.0], "dtype": "f64"},
+                {"op": "const", "result": 3, "operands": [99.0], "dtype": "f64"}
+            ]
+        }"#,
+        );
+
+        let optimized = optimize(&ir, OptLevel::Basic);
+
+        assert!(optimized.ops.iter().all(|op| op.result != 3));
+    }
+
+    #[test]
+    fn cse_does_not_merge_ops_that_only_differ_by_dtype() {
+        let ir = ir_from(
+            r#"{
+            "inputs": {"x": 1, "y": 2},
+            "output": 5,
+            "ops": [
+                {"op": "load", "result": 1, "operands": ["x"], "dtype": "f64"},
+                {"op": "load", "result": 2, "operands": ["y"], "dtype": "f64"},
+                {"op": "add", "result": 3, "operands": [1, 2], "dtype": "f64"},
+                {"op": "add", "result": 4, "operands": [1, 2], "dtype": "i64"},
+                {"op": "mul", "result": 5, "operands": [3, 4], "dtype": "f64"}
+            ]
+        }"#,
+        );
+
+        let optimized = optimize(&ir, OptLevel::Basic);
+
+        // Both adds survive: same opcode and operands, but different dtype,
+        // so they're not the same value and must not be deduplicated.
+        let add_count = optimized.ops.iter().filter(|op| op.op == OpCode::Add).count();
+        assert_eq!(add_count, 2);
+    }
+
+    #[test]
+    fn opt_level_none_is_a_no_op() {
+        let ir = ir_from(
+            r#"{
+            "inputs": {},
+            "output": 3,
+            "ops": [
+                {"op": "const", "result": 1, "operands": [2.0], "dtype": "f64"},
+                {"op": "const", "result": 2, "operands": [3.0], "dtype": "f64"},
+                {"op": "add", "result": 3, "operands": [1, 2], "dtype": "f64"}
+            ]
+        }"#,
+        );
+
+        let unoptimized = optimize(&ir, OptLevel::None);
+        assert_eq!(unoptimized.ops.len(), ir.ops.len());
+    }
+}
@@ -0,0 +1,161 @@
+//! Parallel Batch Compilation
+//!
+//! For programs that trace and submit many independent kernels at once (e.g.
+//! during startup), compiling them one at a time on a single `JitCompiler`
+//! turns per-kernel compile latency into total latency. `WorkerRegistry` fans
+//! a batch of traces out across a thread pool instead, turning that latency
+//! into throughput.
+//!
+//! Each worker gets its own `JitCompiler` (and therefore its own ISA and
+//! `JITModule` state) so compilation itself never contends across threads.
+//! The *results* do cross back over the `thread::spawn`/join boundary (and,
+//! from `PyJitCompiler::compile_batch`, a `py.allow_threads` boundary on top
+//! of that) as `Arc<CompiledFunction>` - that only typechecks because
+//! `CompiledFunction` keeps its `JITModule` behind a `Mutex`, making it
+//! `Sync` despite the module's own non-`Sync` interior state.
+
+use std::sync::Arc;
+use std::thread;
+
+use super::codegen::{CompiledFunction, JitCompiler};
+use super::ir::TraceIR;
+use super::optimizer::OptLevel;
+
+/// Compiles batches of `TraceIR` across a fixed-size thread pool.
+pub struct WorkerRegistry {
+    num_workers: usize,
+    opt_level: OptLevel,
+}
+
+impl WorkerRegistry {
+    /// Create a registry with one worker per available CPU (falling back to
+    /// a single worker if the platform can't report parallelism).
+    pub fn new() -> Self {
+        let num_workers = thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+        Self::with_workers(num_workers)
+    }
+
+    /// Create a registry with an explicit worker count.
+    pub fn with_workers(num_workers: usize) -> Self {
+        Self {
+            num_workers: num_workers.max(1),
+            opt_level: OptLevel::default(),
+        }
+    }
+
+    /// Set the opt level every worker's `JitCompiler` is constructed with.
+    pub fn with_opt_level(mut self, opt_level: OptLevel) -> Self {
+        self.opt_level = opt_level;
+        self
+    }
+
+    /// Compile `traces` across the worker pool.
+    ///
+    /// Results are returned in the same order as `traces`. Each worker
+    /// compiles a contiguous slice on its own `JitCompiler` instance, so a
+    /// failure compiling one trace doesn't affect the others.
+    pub fn compile_batch(&self, traces: Vec<TraceIR>) -> Vec<Result<Arc<CompiledFunction>, String>> {
+        if traces.is_empty() {
+            return Vec::new();
+        }
+
+        let num_workers = self.num_workers.min(traces.len());
+        let chunk_size = traces.len().div_ceil(num_workers);
+
+        let mut handles = Vec::with_capacity(num_workers);
+        let mut remaining = traces;
+        while !remaining.is_empty() {
+            let take = chunk_size.min(remaining.len());
+            let chunk: Vec<TraceIR> = remaining.drain(..take).collect();
+            let opt_level = self.opt_level;
+
+            handles.push(thread::spawn(move || {
+                let compiler = JitCompiler::with_opt_level(opt_level)
+                    .expect("failed to create worker JIT compiler");
+                chunk
+                    .into_iter()
+                    .map(|ir| compiler.compile(&ir))
+                    .collect::<Vec<_>>()
+            }));
+        }
+
+        // Chunks were spawned (and are joined) in the same contiguous order
+        // they were split from `traces`, so flattening preserves input order.
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("JIT worker thread panicked"))
+            .collect()
+    }
+}
+
+impl Default for WorkerRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ir_returning(n: f64) -> TraceIR {
+        TraceIR::from_json(&format!(
+            r#"{{
+                "inputs": {{}},
+                "output": 1,
+                "ops": [{{"op": "const", "result": 1, "operands": [{n}], "dtype": "f64"}}]
+            }}"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn compiles_batch_in_order() {
+        let traces: Vec<TraceIR> = (0..8).map(|i| ir_returning(i as f64)).collect();
+
+        let registry = WorkerRegistry::with_workers(4);
+        let results = registry.compile_batch(traces);
+
+        assert_eq!(results.len(), 8);
+        for (i, result) in results.into_iter().enumerate() {
+            let func = result.unwrap();
+            assert_eq!(func.call(&[]), i as f64);
+        }
+    }
+
+    #[test]
+    fn isolates_per_trace_errors() {
+        let mut traces = vec![ir_returning(1.0)];
+        // Calling an unknown function should fail to compile without
+        // affecting the other (valid) trace in the batch.
+        traces.push(
+            TraceIR::from_json(
+                r#"{
+                    "inputs": {"x": 1},
+                    "output": 2,
+                    "ops": [
+                        {"op": "load", "result": 1, "operands": ["x"], "dtype": "f64"},
+                        {"op": "call", "result": 2, "operands": ["frobnicate", 1], "dtype": "f64"}
+                    ]
+                }"#,
+            )
+            .unwrap(),
+        );
+        traces.push(ir_returning(3.0));
+
+        let registry = WorkerRegistry::with_workers(2);
+        let results = registry.compile_batch(traces);
+
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+        assert!(results[2].is_ok());
+    }
+
+    #[test]
+    fn empty_batch_returns_empty() {
+        let registry = WorkerRegistry::new();
+        assert!(registry.compile_batch(Vec::new()).is_empty());
+    }
+}
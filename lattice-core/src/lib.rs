@@ -17,6 +17,7 @@
 //!
 //! - `reactive`: Core reactive primitives and dependency tracking
 //! - `graph`: Computational dependency graph implementation
+//! - `jit`: Cranelift-based JIT compilation of traced operations
 //! - `render`: Virtual DOM and patch generation
 //! - `transport`: WebSocket server and protocol implementation
 //!
@@ -43,6 +44,7 @@
 
 pub mod reactive;
 pub mod graph;
+pub mod jit;
 
 use pyo3::prelude::*;
 
@@ -54,6 +56,12 @@ use pyo3::prelude::*;
 fn _core(m: &Bound<'_, PyModule>) -> PyResult<()> {
     // Register reactive primitives
     m.add_class::<reactive::PySignal>()?;
+    m.add_class::<reactive::PySignalStream>()?;
+    m.add_class::<reactive::PySelector>()?;
+
+    // Register JIT compilation primitives
+    m.add_class::<jit::PyJitCompiler>()?;
+    m.add_class::<jit::PyCompiledFunction>()?;
 
     // Add version info
     m.add("__version__", env!("CARGO_PKG_VERSION"))?;
@@ -0,0 +1,430 @@
+//! Async Memo
+//!
+//! [`Memo`](super::memo::Memo) assumes `compute` is synchronous and cheap
+//! enough to run on whichever thread calls `get`. An [`AsyncMemo`] is for
+//! derived values that come from a `Future` instead - an HTTP request, a
+//! database query - mirroring the Leptos pattern of pairing a reactive graph
+//! with async "resources" whose values arrive later.
+//!
+//! # How It Works
+//!
+//! 1. On the first [`AsyncMemo::get_state`] while `Dirty`, the memo calls
+//!    `compute` to build the future, spawns it on an injectable executor,
+//!    and immediately returns [`AsyncMemoState::Pending`] - the calling
+//!    thread never blocks waiting for the future to resolve.
+//!
+//! 2. Further calls to `get_state` while the spawned future is still running
+//!    also return `Pending`.
+//!
+//! 3. When the future completes, its result is cached and the memo flips to
+//!    `Ready`, and its dependents are notified so anything downstream that already
+//!    read this memo (and got `Pending`) knows to re-check it.
+//!
+//! # Dependency Tracking and Re-dispatch
+//!
+//! `compute` is called inside a [`ReactiveContext`], so any signals read
+//! while building the future (not inside the future itself, which runs later
+//! with no context) are captured as dependencies, the same as
+//! [`Memo::recompute`](super::memo::Memo). When one of those dependencies
+//! changes, the runtime calls [`Reactive::mark_maybe_dirty`] on this memo,
+//! which forces it back to `Dirty` so the next `get_state` redispatches.
+//! [`AsyncMemo::redispatch`] does the same thing manually, for callers that
+//! want to invalidate the cached value without a tracked dependency - e.g. on
+//! a timer, or after an external write the reactive graph doesn't see.
+//!
+//! Forcing `Dirty` while a dispatch is still in flight doesn't cancel that
+//! in-flight future - it will still land and flip the memo to `Ready` when it
+//! completes, racing with whatever a subsequent dispatch produces. There's no
+//! generation counter ruling out a stale dispatch, so a dependency that
+//! changes faster than the future resolves can make an older result win.
+//!
+//! # Injectable Executor
+//!
+//! `compute` only builds the future; something still has to poll it to
+//! completion. [`AsyncMemo::new`] spawns onto `tokio` (already a dependency
+//! via [`crate::reactive::stream`]'s async bridging), but
+//! [`AsyncMemo::with_spawner`] accepts any [`Spawner`], so tests (or a
+//! non-tokio host application) can supply their own.
+
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+use super::context::ReactiveContext;
+use super::runtime::{Reactive, ReactiveHandle, Runtime};
+use super::subscriber::SubscriberId;
+
+/// Counter for generating unique async memo IDs.
+static ASYNC_MEMO_ID_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Generate a new unique async memo ID.
+fn next_async_memo_id() -> u64 {
+    ASYNC_MEMO_ID_COUNTER.fetch_add(1, Ordering::Relaxed)
+}
+
+/// A boxed, type-erased future, the common currency between `compute` and
+/// the [`Spawner`] that runs it.
+type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+/// Runs a boxed future to completion somewhere - a `tokio` task by default,
+/// or whatever a test or host application injects via
+/// [`AsyncMemo::with_spawner`]. Takes `Arc<dyn Fn>` rather than a generic so
+/// `AsyncMemo<T>` doesn't need a type parameter for it.
+pub type Spawner = Arc<dyn Fn(BoxFuture<()>) + Send + Sync>;
+
+/// The default [`Spawner`], handing the future to `tokio::spawn`.
+fn tokio_spawner() -> Spawner {
+    Arc::new(|fut| {
+        tokio::spawn(fut);
+    })
+}
+
+/// A `Poll`-like snapshot of an [`AsyncMemo`]'s state, returned by
+/// [`AsyncMemo::get_state`]. Never blocks - `Pending` just means the spawned
+/// future hasn't resolved yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AsyncMemoState<T> {
+    /// The computation hasn't produced a value yet - either it's still
+    /// running, or nothing has read this memo since it was last dirtied.
+    Pending,
+
+    /// The computation completed; this is its most recently resolved value.
+    Ready(T),
+}
+
+/// Internal dirty state - simpler than [`MemoState`](super::memo::MemoState):
+/// there's no lazy verification step here, since a changed dependency always
+/// forces a full redispatch rather than a cheap revision comparison.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AsyncStatus {
+    /// Needs a fresh dispatch on the next `get_state`.
+    Dirty,
+
+    /// A dispatch is in flight; no value to report yet.
+    Pending,
+
+    /// The cached value reflects the most recently completed dispatch.
+    Ready,
+}
+
+/// The state shared by every clone of an [`AsyncMemo`], and the type
+/// actually registered with the [`Runtime`] as a [`Reactive`].
+struct AsyncMemoInner<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    subscriber_id: SubscriberId,
+    compute: Box<dyn Fn() -> BoxFuture<T> + Send + Sync>,
+    spawner: Spawner,
+    value: RwLock<Option<T>>,
+    status: RwLock<AsyncStatus>,
+    dependencies: RwLock<HashSet<u64>>,
+    dependents: RwLock<HashSet<SubscriberId>>,
+}
+
+impl<T> Reactive for AsyncMemoInner<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    fn subscriber_id(&self) -> SubscriberId {
+        self.subscriber_id
+    }
+
+    fn mark_maybe_dirty(&self) {
+        *self.status.write().expect("status lock poisoned") = AsyncStatus::Dirty;
+    }
+
+    fn schedule(&self) {
+        // Async memos are lazy - there's nothing to run eagerly. The next
+        // `get_state` dispatches once `status` is `Dirty`.
+    }
+
+    fn is_eager(&self) -> bool {
+        false
+    }
+}
+
+/// A derived value computed by a spawned `Future` rather than a synchronous
+/// closure - see the module docs.
+pub struct AsyncMemo<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    id: u64,
+    inner: Arc<AsyncMemoInner<T>>,
+    runtime_handle: Arc<ReactiveHandle>,
+}
+
+impl<T> AsyncMemo<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    /// Create a new async memo, spawning dispatched futures onto `tokio`.
+    ///
+    /// The computation is not run immediately - it dispatches on the first
+    /// [`Self::get_state`].
+    pub fn new<F, Fut>(compute: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = T> + Send + 'static,
+    {
+        Self::with_spawner(compute, tokio_spawner())
+    }
+
+    /// Create a new async memo with an injected [`Spawner`] instead of the
+    /// default `tokio` one - useful for tests, or a host application that
+    /// isn't running a `tokio` runtime.
+    pub fn with_spawner<F, Fut>(compute: F, spawner: Spawner) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = T> + Send + 'static,
+    {
+        let inner = Arc::new(AsyncMemoInner {
+            subscriber_id: SubscriberId::new(),
+            compute: Box::new(move || Box::pin(compute()) as BoxFuture<T>),
+            spawner,
+            value: RwLock::new(None),
+            status: RwLock::new(AsyncStatus::Dirty),
+            dependencies: RwLock::new(HashSet::new()),
+            dependents: RwLock::new(HashSet::new()),
+        });
+
+        let runtime_handle = Arc::new(Runtime::register(Arc::clone(&inner) as Arc<dyn Reactive>));
+
+        Self {
+            id: next_async_memo_id(),
+            inner,
+            runtime_handle,
+        }
+    }
+
+    /// Get the memo's unique ID.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Get the subscriber ID for this memo.
+    pub fn subscriber_id(&self) -> SubscriberId {
+        self.inner.subscriber_id
+    }
+
+    /// Get the current state without blocking. Dispatches the computation on
+    /// the first call after being `Dirty` (initially, or after
+    /// [`Self::redispatch`]/a tracked dependency changing), returning
+    /// `Pending` immediately rather than waiting for it to resolve.
+    pub fn get_state(&self) -> AsyncMemoState<T> {
+        // If we're inside a reactive context, track this memo as a
+        // dependency, same as `Memo::get` / `Signal::get`.
+        if ReactiveContext::is_active() {
+            if let Some(current_subscriber) = ReactiveContext::current_subscriber() {
+                self.inner
+                    .dependents
+                    .write()
+                    .expect("dependents lock poisoned")
+                    .insert(current_subscriber);
+                Runtime::add_subscriber_dependency(self.inner.subscriber_id, current_subscriber);
+            }
+        }
+
+        let should_dispatch = {
+            let mut status = self.inner.status.write().expect("status lock poisoned");
+            if *status == AsyncStatus::Dirty {
+                *status = AsyncStatus::Pending;
+                true
+            } else {
+                false
+            }
+        };
+
+        if should_dispatch {
+            self.dispatch();
+        }
+
+        match *self.inner.status.read().expect("status lock poisoned") {
+            AsyncStatus::Ready => AsyncMemoState::Ready(
+                self.inner
+                    .value
+                    .read()
+                    .expect("value lock poisoned")
+                    .clone()
+                    .expect("ready async memo should have a value"),
+            ),
+            AsyncStatus::Dirty | AsyncStatus::Pending => AsyncMemoState::Pending,
+        }
+    }
+
+    /// Force the memo back to `Dirty`, so the next [`Self::get_state`]
+    /// redispatches the computation - the manual counterpart to a tracked
+    /// dependency changing (see "Dependency Tracking and Re-dispatch" in the
+    /// module docs).
+    pub fn redispatch(&self) {
+        self.inner.mark_maybe_dirty();
+    }
+
+    /// Build the future via `compute` (tracking dependencies) and hand it to
+    /// the spawner. Does not block - the spawned task stores its result and
+    /// notifies dependents once it resolves.
+    fn dispatch(&self) {
+        let future = {
+            let _ctx = ReactiveContext::enter(self.inner.subscriber_id);
+            (self.inner.compute)()
+        };
+
+        let new_deps: HashSet<u64> = ReactiveContext::get_dependencies().into_iter().collect();
+        *self
+            .inner
+            .dependencies
+            .write()
+            .expect("dependencies lock poisoned") = new_deps;
+
+        let inner = Arc::clone(&self.inner);
+        let task: BoxFuture<()> = Box::pin(async move {
+            let result = future.await;
+
+            *inner.value.write().expect("value lock poisoned") = Some(result);
+            *inner.status.write().expect("status lock poisoned") = AsyncStatus::Ready;
+
+            let dependents: Vec<SubscriberId> = inner
+                .dependents
+                .read()
+                .expect("dependents lock poisoned")
+                .iter()
+                .copied()
+                .collect();
+            Runtime::notify_subscribers_directly(dependents);
+        });
+
+        (self.inner.spawner)(task);
+    }
+
+    /// Check if the memo has ever produced a value.
+    pub fn has_value(&self) -> bool {
+        self.inner
+            .value
+            .read()
+            .expect("value lock poisoned")
+            .is_some()
+    }
+
+    /// Get the number of dependents.
+    pub fn dependent_count(&self) -> usize {
+        self.inner
+            .dependents
+            .read()
+            .expect("dependents lock poisoned")
+            .len()
+    }
+}
+
+impl<T> Clone for AsyncMemo<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            inner: Arc::clone(&self.inner),
+            runtime_handle: Arc::clone(&self.runtime_handle),
+        }
+    }
+}
+
+impl<T> Debug for AsyncMemo<T>
+where
+    T: Clone + Send + Sync + Debug + 'static,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AsyncMemo")
+            .field("id", &self.id)
+            .field("has_value", &self.has_value())
+            .field("dependent_count", &self.dependent_count())
+            .finish()
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Tests
+// ----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicI32;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn async_memo_starts_pending_and_dispatches_on_first_read() {
+        let call_count = Arc::new(AtomicI32::new(0));
+        let call_count_clone = call_count.clone();
+
+        let memo = AsyncMemo::with_spawner(
+            move || {
+                let call_count = call_count_clone.clone();
+                async move {
+                    call_count.fetch_add(1, Ordering::SeqCst);
+                    42
+                }
+            },
+            tokio_spawner(),
+        );
+
+        assert!(!memo.has_value());
+
+        assert_eq!(memo.get_state(), AsyncMemoState::Pending);
+
+        // Reading again while still in flight should not re-dispatch.
+        assert_eq!(memo.get_state(), AsyncMemoState::Pending);
+        assert_eq!(call_count.load(Ordering::SeqCst), 0);
+
+        // `tokio::spawn` only schedules the task - it doesn't poll it
+        // synchronously, so under the default current-thread test runtime
+        // the future hasn't actually run yet until we yield once. Exactly
+        // one future runs then, since the second read spawned nothing.
+        tokio::task::yield_now().await;
+        assert_eq!(call_count.load(Ordering::SeqCst), 1);
+
+        // Give the spawned task a chance to run and resolve.
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        assert_eq!(memo.get_state(), AsyncMemoState::Ready(42));
+        assert!(memo.has_value());
+    }
+
+    #[tokio::test]
+    async fn redispatch_forces_a_fresh_computation() {
+        let counter = Arc::new(AtomicI32::new(0));
+        let counter_clone = counter.clone();
+
+        let memo = AsyncMemo::with_spawner(
+            move || {
+                let counter = counter_clone.clone();
+                async move { counter.fetch_add(1, Ordering::SeqCst) + 1 }
+            },
+            tokio_spawner(),
+        );
+
+        memo.get_state();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(memo.get_state(), AsyncMemoState::Ready(1));
+
+        memo.redispatch();
+        assert_eq!(memo.get_state(), AsyncMemoState::Pending);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(memo.get_state(), AsyncMemoState::Ready(2));
+    }
+
+    #[tokio::test]
+    async fn async_memo_clone_shares_state() {
+        let memo1 = AsyncMemo::with_spawner(|| async { 7 }, tokio_spawner());
+        let memo2 = memo1.clone();
+
+        assert_eq!(memo1.id(), memo2.id());
+        memo1.get_state();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        assert_eq!(memo2.get_state(), AsyncMemoState::Ready(7));
+    }
+}
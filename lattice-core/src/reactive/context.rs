@@ -14,6 +14,7 @@
 //! from another memo).
 
 use std::cell::RefCell;
+use std::sync::Arc;
 use super::SubscriberId;
 
 /// The reactive context stack.
@@ -25,16 +26,38 @@ thread_local! {
     static CONTEXT_STACK: RefCell<Vec<ContextEntry>> = RefCell::new(Vec::new());
 }
 
+/// Type-erased handle to a memo read during the currently executing
+/// computation, so that computation's own `Memo::verify` (if it is one) can
+/// later compare its `verified_at` against this upstream memo's last actual
+/// change - the memo-of-memo counterpart to a raw signal id in
+/// [`ContextEntry::dependencies`].
+///
+/// Lives here rather than in `memo.rs` so [`ContextEntry`] can hold it
+/// without `context.rs` depending on `memo.rs`; `Memo<T>` implements this
+/// trait over in `memo.rs` instead.
+pub(crate) trait MemoDependency: Send + Sync {
+    /// The subscriber ID of the memo this handle refers to, used to dedupe
+    /// repeated reads of the same upstream memo within one computation.
+    fn subscriber_id(&self) -> SubscriberId;
+
+    /// Resolve this memo to a valid value (recomputing or verifying it,
+    /// transitively, as needed) and return the revision its value last
+    /// actually changed at.
+    fn resolve_changed_at(&self) -> u64;
+}
+
 /// An entry in the reactive context stack.
 ///
 /// Contains information about the currently executing computation.
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 struct ContextEntry {
     /// The subscriber ID of the current computation.
     subscriber_id: SubscriberId,
     /// Dependencies collected during this computation.
     /// These are the signal IDs that were read.
     dependencies: Vec<u64>,
+    /// Upstream memos read during this computation - see [`MemoDependency`].
+    memo_dependencies: Vec<Arc<dyn MemoDependency>>,
 }
 
 /// Guard that pops the context when dropped.
@@ -57,6 +80,7 @@ impl ReactiveContext {
             stack.borrow_mut().push(ContextEntry {
                 subscriber_id,
                 dependencies: Vec::new(),
+                memo_dependencies: Vec::new(),
             });
         });
 
@@ -75,6 +99,25 @@ impl ReactiveContext {
         })
     }
 
+    /// Whether `subscriber_id` is anywhere on this thread's context stack -
+    /// not just the innermost entry ([`current_subscriber`](Self::current_subscriber)),
+    /// but also a computation further out that this one is nested inside.
+    ///
+    /// Used by `Runtime::sweep` to avoid re-scheduling a subscriber that is
+    /// already executing: if a memo it reads recomputes and pushes a change
+    /// notification back to it mid-execution, that subscriber will observe
+    /// the fresh value from its own in-flight read before returning, so
+    /// scheduling it again would be redundant at best (a wasted re-run) and
+    /// at worst reenter a non-reentrant lock it's currently holding.
+    pub fn is_subscriber_active(subscriber_id: SubscriberId) -> bool {
+        CONTEXT_STACK.with(|stack| {
+            stack
+                .borrow()
+                .iter()
+                .any(|entry| entry.subscriber_id == subscriber_id)
+        })
+    }
+
     /// Record a dependency on the given signal.
     ///
     /// This is called by signals when they are read.
@@ -96,6 +139,36 @@ impl ReactiveContext {
                 .unwrap_or_default()
         })
     }
+
+    /// Record a dependency on an upstream memo - the memo-of-memo
+    /// counterpart to [`track_dependency`](Self::track_dependency). Dedupes
+    /// against anything already recorded this computation by subscriber id,
+    /// so reading the same upstream memo more than once doesn't multiply the
+    /// work [`Memo::verify`](super::memo::Memo::verify) does per check.
+    pub(crate) fn track_memo_dependency(dep: Arc<dyn MemoDependency>) {
+        CONTEXT_STACK.with(|stack| {
+            if let Some(entry) = stack.borrow_mut().last_mut() {
+                if !entry
+                    .memo_dependencies
+                    .iter()
+                    .any(|existing| existing.subscriber_id() == dep.subscriber_id())
+                {
+                    entry.memo_dependencies.push(dep);
+                }
+            }
+        });
+    }
+
+    /// Get the memo dependencies collected in the current context.
+    pub(crate) fn get_memo_dependencies() -> Vec<Arc<dyn MemoDependency>> {
+        CONTEXT_STACK.with(|stack| {
+            stack
+                .borrow()
+                .last()
+                .map(|entry| entry.memo_dependencies.clone())
+                .unwrap_or_default()
+        })
+    }
 }
 
 impl Drop for ReactiveContext {
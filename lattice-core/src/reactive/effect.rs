@@ -33,12 +33,26 @@
 //! Effects can optionally return a cleanup function. This function is called
 //! before the effect re-runs and when the effect is disposed. This is useful
 //! for cleaning up resources like event listeners or timers.
-
+//!
+//! # Runtime Registration
+//!
+//! Every effect registers its shared inner state with [`Runtime::register`]
+//! (see [`Reactive`]), so a dependency elsewhere in the graph can mark and
+//! schedule it without knowing its concrete type - see
+//! `runtime::Runtime::mark_subscribers`/`sweep`. The returned
+//! [`ReactiveHandle`] is kept in its own field, separate from the registered
+//! object itself, so the registry's own strong reference to that object can
+//! never keep the handle (and so the registration) alive forever - see
+//! [`Effect`]'s field docs.
+
+use std::cell::RefCell;
 use std::sync::atomic::{AtomicU64, Ordering, AtomicBool};
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
 use std::collections::HashSet;
 
 use super::context::ReactiveContext;
+use super::runtime::{Reactive, ReactiveHandle, Runtime};
+use super::scope::{Disposable, Owner};
 use super::subscriber::SubscriberId;
 
 /// Counter for generating unique effect IDs.
@@ -49,6 +63,187 @@ fn next_effect_id() -> u64 {
     EFFECT_ID_COUNTER.fetch_add(1, Ordering::Relaxed)
 }
 
+/// A boxed, type-erased effect cleanup callback.
+type Cleanup = Box<dyn FnOnce() + Send + Sync>;
+
+/// Stack of pending `on_cleanup` registrations, one frame per effect
+/// currently executing on this thread.
+///
+/// `Effect::execute` pushes a fresh frame before running its function and
+/// pops it afterward, folding whatever was registered into the effect's
+/// stored cleanup. A thread-local mirrors `ReactiveContext`'s dependency
+/// stack since cleanup registration has the same "ambient, nested" shape as
+/// dependency tracking.
+thread_local! {
+    static CLEANUP_STACK: RefCell<Vec<Vec<Cleanup>>> = RefCell::new(Vec::new());
+}
+
+/// Register a callback to run before the currently executing effect re-runs
+/// or is disposed.
+///
+/// Must be called from within the function passed to [`Effect::new`] or
+/// [`Effect::new_with_cleanup`]; outside of a running effect, this is a no-op.
+pub fn on_cleanup<F>(f: F)
+where
+    F: FnOnce() + Send + Sync + 'static,
+{
+    CLEANUP_STACK.with(|stack| {
+        if let Some(frame) = stack.borrow_mut().last_mut() {
+            frame.push(Box::new(f));
+        }
+    });
+}
+
+/// A no-op cleanup used by [`Effect::new`], whose plain `Fn()` has nothing to
+/// tear down via the return-a-cleanup convention (callers still get
+/// `on_cleanup` inside the effect body).
+fn no_cleanup() {}
+
+/// The state shared by every clone of an [`Effect`], and the type actually
+/// registered with the [`Runtime`] as a [`Reactive`].
+struct EffectInner {
+    /// The subscriber ID used for dependency tracking.
+    subscriber_id: SubscriberId,
+
+    /// The effect function, returning the cleanup to run before the next
+    /// re-run or disposal. Wrapped in a `Mutex` since the user's function may
+    /// be `FnMut` (it can return a different cleanup closure each call).
+    /// Boxed because a bare `dyn FnMut` isn't `Sized`, and it isn't the last
+    /// field of this struct.
+    run: Mutex<Box<dyn FnMut() -> Cleanup + Send>>,
+
+    /// Signal IDs that this effect depends on.
+    dependencies: RwLock<HashSet<u64>>,
+
+    /// Whether the effect has been disposed.
+    disposed: AtomicBool,
+
+    /// Number of times the effect has run.
+    run_count: RwLock<usize>,
+
+    /// Cleanup pending from the previous run (the function's returned
+    /// cleanup folded together with any `on_cleanup` registrations), run
+    /// before the next `execute()` and on `dispose()`.
+    cleanup: RwLock<Option<Cleanup>>,
+
+    /// Owner for whatever this effect's function creates while it runs
+    /// (e.g. a nested `Effect::new`). Disposed - and therefore cleared -
+    /// before each re-run and on this effect's own disposal, so a child
+    /// created by one execution never outlives it. See `reactive::scope`.
+    children_owner: Arc<Owner>,
+}
+
+impl EffectInner {
+    fn execute(&self) {
+        if self.disposed.load(Ordering::SeqCst) {
+            return;
+        }
+
+        // Run any cleanup left over from the previous execution before
+        // re-running, so torn-down resources never overlap with new ones.
+        self.run_pending_cleanup();
+
+        // Dispose the previous generation of child effects (anything this
+        // effect's function created the last time it ran), so re-running
+        // never accumulates stale subscribers - see `reactive::scope`.
+        self.children_owner.dispose_children();
+
+        // Clear old dependencies
+        self.dependencies
+            .write()
+            .expect("dependencies lock poisoned")
+            .clear();
+
+        // Enter a reactive context to track dependencies
+        let _ctx = ReactiveContext::enter(self.subscriber_id);
+
+        // Collect `on_cleanup` registrations made during this run.
+        CLEANUP_STACK.with(|stack| stack.borrow_mut().push(Vec::new()));
+
+        // Run the effect function with its own owner active, so anything it
+        // creates (e.g. a nested `Effect::new`) becomes a child of this
+        // execution rather than leaking into whatever scope is active
+        // outside this effect.
+        let returned_cleanup = self
+            .children_owner
+            .with_active(|| (*self.run.lock().expect("effect run lock poisoned"))());
+
+        let on_cleanup_callbacks = CLEANUP_STACK
+            .with(|stack| stack.borrow_mut().pop())
+            .unwrap_or_default();
+
+        *self.cleanup.write().expect("cleanup lock poisoned") = Some(Box::new(move || {
+            for callback in on_cleanup_callbacks {
+                callback();
+            }
+            returned_cleanup();
+        }));
+
+        // Get the dependencies that were accessed during execution
+        let new_deps: HashSet<u64> = ReactiveContext::get_dependencies()
+            .into_iter()
+            .collect();
+
+        // Update our dependency set
+        *self.dependencies.write().expect("dependencies lock poisoned") = new_deps;
+
+        // Increment run count
+        *self.run_count.write().expect("run_count lock poisoned") += 1;
+    }
+
+    /// Take and run the cleanup stored from the previous execution, if any.
+    fn run_pending_cleanup(&self) {
+        let pending = self.cleanup.write().expect("cleanup lock poisoned").take();
+        if let Some(cleanup) = pending {
+            cleanup();
+        }
+    }
+
+    fn dispose(&self) {
+        self.disposed.store(true, Ordering::SeqCst);
+        self.run_pending_cleanup();
+        self.children_owner.dispose_children();
+    }
+
+    fn is_disposed(&self) -> bool {
+        self.disposed.load(Ordering::SeqCst)
+    }
+
+    fn run_count(&self) -> usize {
+        *self.run_count.read().expect("run_count lock poisoned")
+    }
+
+    fn dependency_count(&self) -> usize {
+        self.dependencies
+            .read()
+            .expect("dependencies lock poisoned")
+            .len()
+    }
+}
+
+impl Reactive for EffectInner {
+    fn subscriber_id(&self) -> SubscriberId {
+        self.subscriber_id
+    }
+
+    fn mark_maybe_dirty(&self) {
+        // Effects are eager: there's no "maybe" state to track between the
+        // mark and sweep phases, only `schedule` (run or don't).
+    }
+
+    fn schedule(&self) {
+        if !self.disposed.load(Ordering::SeqCst) {
+            // In a full implementation, this would add the effect to a scheduler
+            // queue. For now, we run synchronously.
+            self.execute();
+        }
+    }
+
+    fn is_eager(&self) -> bool {
+        true
+    }
+}
+
 /// A side-effecting computation that runs when dependencies change.
 ///
 /// # Example
@@ -66,20 +261,20 @@ pub struct Effect {
     /// Unique identifier for this effect.
     id: u64,
 
-    /// The subscriber ID used for dependency tracking.
-    subscriber_id: SubscriberId,
-
-    /// The effect function.
-    run: Arc<dyn Fn() + Send + Sync>,
+    /// State shared by every clone of this effect - also the object
+    /// registered with the [`Runtime`] as a [`Reactive`].
+    inner: Arc<EffectInner>,
 
-    /// Signal IDs that this effect depends on.
-    dependencies: Arc<RwLock<HashSet<u64>>>,
-
-    /// Whether the effect has been disposed.
-    disposed: Arc<AtomicBool>,
-
-    /// Number of times the effect has run.
-    run_count: Arc<RwLock<usize>>,
+    /// Keeps this effect's runtime registration alive for as long as any
+    /// clone of this `Effect` is held, so a dependency elsewhere in the
+    /// graph can still reach it via `Runtime::mark_subscribers`/`sweep`.
+    ///
+    /// Deliberately a field of `Effect`, not of `EffectInner` - `EffectInner`
+    /// is the value `Runtime::register` stores a reference to, so if the
+    /// handle lived there too, the registry's own strong reference would
+    /// keep the handle (and so the registration) alive forever, and it could
+    /// never be unregistered.
+    runtime_handle: Arc<ReactiveHandle>,
 }
 
 impl Effect {
@@ -90,14 +285,10 @@ impl Effect {
     where
         F: Fn() + Send + Sync + 'static,
     {
-        let effect = Self {
-            id: next_effect_id(),
-            subscriber_id: SubscriberId::new(),
-            run: Arc::new(run),
-            dependencies: Arc::new(RwLock::new(HashSet::new())),
-            disposed: Arc::new(AtomicBool::new(false)),
-            run_count: Arc::new(RwLock::new(0)),
-        };
+        let effect = Self::build(move || {
+            run();
+            no_cleanup as fn()
+        });
 
         // Run immediately to establish dependencies
         effect.execute();
@@ -112,14 +303,60 @@ impl Effect {
     where
         F: Fn() + Send + Sync + 'static,
     {
-        Self {
-            id: next_effect_id(),
+        Self::build(move || {
+            run();
+            no_cleanup as fn()
+        })
+    }
+
+    /// Create a new effect whose function returns a cleanup closure.
+    ///
+    /// The cleanup is called right before the effect re-runs (at the top of
+    /// the next [`execute`](Self::execute)) and when the effect is
+    /// [`dispose`](Self::dispose)d, in addition to anything registered via
+    /// [`on_cleanup`] during that run. This is the place to tear down
+    /// timers, listeners, or subscriptions the effect set up.
+    ///
+    /// The function runs immediately to establish initial dependencies.
+    pub fn new_with_cleanup<F, C>(run: F) -> Self
+    where
+        F: FnMut() -> C + Send + 'static,
+        C: FnOnce() + Send + Sync + 'static,
+    {
+        let effect = Self::build(run);
+        effect.execute();
+        effect
+    }
+
+    fn build<F, C>(mut run: F) -> Self
+    where
+        F: FnMut() -> C + Send + 'static,
+        C: FnOnce() + Send + Sync + 'static,
+    {
+        let inner = Arc::new(EffectInner {
             subscriber_id: SubscriberId::new(),
-            run: Arc::new(run),
-            dependencies: Arc::new(RwLock::new(HashSet::new())),
-            disposed: Arc::new(AtomicBool::new(false)),
-            run_count: Arc::new(RwLock::new(0)),
-        }
+            run: Mutex::new(Box::new(move || -> Cleanup { Box::new(run()) })),
+            dependencies: RwLock::new(HashSet::new()),
+            disposed: AtomicBool::new(false),
+            run_count: RwLock::new(0),
+            cleanup: RwLock::new(None),
+            children_owner: Owner::new(),
+        });
+
+        let runtime_handle = Arc::new(Runtime::register(Arc::clone(&inner) as Arc<dyn Reactive>));
+
+        let effect = Self {
+            id: next_effect_id(),
+            inner,
+            runtime_handle,
+        };
+
+        // If a scope (or another effect) is currently active on this
+        // thread, register with it so disposing that owner disposes this
+        // effect too. A no-op outside of any scope.
+        Owner::register_with_current(Arc::new(effect.clone()) as Arc<dyn Disposable>);
+
+        effect
     }
 
     /// Get the effect's unique ID.
@@ -129,75 +366,43 @@ impl Effect {
 
     /// Get the subscriber ID for this effect.
     pub fn subscriber_id(&self) -> SubscriberId {
-        self.subscriber_id
+        self.inner.subscriber_id
     }
 
     /// Execute the effect function.
     ///
     /// This runs the function within a reactive context to track dependencies.
     pub fn execute(&self) {
-        if self.disposed.load(Ordering::SeqCst) {
-            return;
-        }
-
-        // Clear old dependencies
-        self.dependencies
-            .write()
-            .expect("dependencies lock poisoned")
-            .clear();
-
-        // Enter a reactive context to track dependencies
-        let _ctx = ReactiveContext::enter(self.subscriber_id);
-
-        // Run the effect function
-        (self.run)();
-
-        // Get the dependencies that were accessed during execution
-        let new_deps: HashSet<u64> = ReactiveContext::get_dependencies()
-            .into_iter()
-            .collect();
-
-        // Update our dependency set
-        *self.dependencies.write().expect("dependencies lock poisoned") = new_deps;
-
-        // Increment run count
-        *self.run_count.write().expect("run_count lock poisoned") += 1;
+        self.inner.execute();
     }
 
     /// Schedule the effect to re-run.
     ///
     /// Called when a dependency changes.
     pub fn schedule(&self) {
-        if !self.disposed.load(Ordering::SeqCst) {
-            // In a full implementation, this would add the effect to a scheduler
-            // queue. For now, we run synchronously.
-            self.execute();
-        }
+        self.inner.schedule();
     }
 
     /// Dispose of the effect.
     ///
     /// After disposal, the effect will not run again.
     pub fn dispose(&self) {
-        self.disposed.store(true, Ordering::SeqCst);
+        self.inner.dispose();
     }
 
     /// Check if the effect has been disposed.
     pub fn is_disposed(&self) -> bool {
-        self.disposed.load(Ordering::SeqCst)
+        self.inner.is_disposed()
     }
 
     /// Get the number of times the effect has run.
     pub fn run_count(&self) -> usize {
-        *self.run_count.read().expect("run_count lock poisoned")
+        self.inner.run_count()
     }
 
     /// Get the number of dependencies.
     pub fn dependency_count(&self) -> usize {
-        self.dependencies
-            .read()
-            .expect("dependencies lock poisoned")
-            .len()
+        self.inner.dependency_count()
     }
 }
 
@@ -205,15 +410,18 @@ impl Clone for Effect {
     fn clone(&self) -> Self {
         Self {
             id: self.id,
-            subscriber_id: self.subscriber_id,
-            run: Arc::clone(&self.run),
-            dependencies: Arc::clone(&self.dependencies),
-            disposed: Arc::clone(&self.disposed),
-            run_count: Arc::clone(&self.run_count),
+            inner: Arc::clone(&self.inner),
+            runtime_handle: Arc::clone(&self.runtime_handle),
         }
     }
 }
 
+impl Disposable for Effect {
+    fn dispose(&self) {
+        Effect::dispose(self);
+    }
+}
+
 impl std::fmt::Debug for Effect {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Effect")
@@ -344,4 +552,91 @@ mod tests {
         effect1.dispose();
         assert!(effect2.is_disposed());
     }
+
+    #[test]
+    fn cleanup_runs_before_each_rerun_not_on_first_run() {
+        let cleanup_count = Arc::new(AtomicI32::new(0));
+        let cleanup_count_clone = cleanup_count.clone();
+
+        let effect = Effect::new_with_cleanup(move || {
+            let cleanup_count = cleanup_count_clone.clone();
+            move || {
+                cleanup_count.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+
+        // Cleanup shouldn't run before the first execution.
+        assert_eq!(cleanup_count.load(Ordering::SeqCst), 0);
+
+        effect.execute();
+        assert_eq!(cleanup_count.load(Ordering::SeqCst), 1);
+
+        effect.execute();
+        assert_eq!(cleanup_count.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn cleanup_runs_on_dispose() {
+        let cleaned_up = Arc::new(AtomicBool::new(false));
+        let cleaned_up_clone = cleaned_up.clone();
+
+        let effect = Effect::new_with_cleanup(move || {
+            let cleaned_up = cleaned_up_clone.clone();
+            move || {
+                cleaned_up.store(true, Ordering::SeqCst);
+            }
+        });
+
+        assert!(!cleaned_up.load(Ordering::SeqCst));
+
+        effect.dispose();
+        assert!(cleaned_up.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn on_cleanup_registration_runs_before_next_execution() {
+        use super::on_cleanup;
+
+        let cleanup_count = Arc::new(AtomicI32::new(0));
+        let cleanup_count_clone = cleanup_count.clone();
+
+        let effect = Effect::new(move || {
+            let cleanup_count = cleanup_count_clone.clone();
+            on_cleanup(move || {
+                cleanup_count.fetch_add(1, Ordering::SeqCst);
+            });
+        });
+
+        assert_eq!(cleanup_count.load(Ordering::SeqCst), 0);
+
+        effect.execute();
+        assert_eq!(cleanup_count.load(Ordering::SeqCst), 1);
+
+        effect.dispose();
+        assert_eq!(cleanup_count.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn on_cleanup_outside_a_running_effect_is_a_no_op() {
+        use super::on_cleanup;
+
+        // Should not panic, and has nothing to register against.
+        on_cleanup(|| panic!("should never run"));
+    }
+
+    #[test]
+    fn disposed_effect_is_unregistered_once_all_clones_drop() {
+        // Regression test for the runtime-registration lifetime: dropping
+        // every clone of an effect should let its `Reactive` registration go
+        // away too, rather than leaking an entry the registry can never
+        // upgrade.
+        let effect = Effect::new(|| {});
+        let subscriber_id = effect.subscriber_id();
+
+        drop(effect);
+
+        // Marking a subscriber ID that nothing is registered under anymore
+        // is simply a no-op - this should not panic.
+        Runtime::notify_subscribers_directly([subscriber_id]);
+    }
 }
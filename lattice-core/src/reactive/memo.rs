@@ -15,6 +15,26 @@
 //!
 //! 5. If inputs changed, recompute. Otherwise, mark clean and return cache.
 //!
+//! # Lazy Verification
+//!
+//! Step 4 above is Salsa-style red/green verification, not a recompute: a
+//! `MaybeDirty` memo means *some* dependency marked it, not that one
+//! necessarily produced a different value. Rather than recomputing on every
+//! `MaybeDirty`, the memo walks its dependencies and compares each one's
+//! last-changed revision (tracked globally by
+//! [`Runtime`](super::runtime::Runtime)) against `verified_at`, the revision
+//! this memo was last confirmed valid at. If nothing has actually changed
+//! since then, the memo is "green": it's marked `Clean`, `verified_at`
+//! advances to the current revision, and the cached value is returned with
+//! no call to `compute` at all. Only a dependency that changed more recently
+//! forces a real recompute.
+//!
+//! After a recompute, if the freshly computed value equals the previous one
+//! (per `PartialEq`), `changed_at` is *not* advanced - this backdating is
+//! what lets a memo act as a firewall: a downstream consumer that depends on
+//! this memo sees no change at this revision, even though this memo itself
+//! re-ran.
+//!
 //! # Why This Matters
 //!
 //! This "lazy" approach avoids unnecessary recomputation:
@@ -24,19 +44,78 @@
 //! - Only the memos actually accessed will recompute
 //! - Memos that are never read stay dirty (no wasted work)
 //!
+//! # Propagation
+//!
+//! A memo registers its shared inner state with [`Runtime::register`] (see
+//! [`Reactive`]), the same way an [`Effect`](super::effect::Effect) does, so
+//! `notify_dependents` can reach an arbitrary dependent - another memo or an
+//! effect - without knowing its concrete type. `recompute` calls
+//! `notify_dependents` only when `value_changed`, which marks every
+//! dependent `MaybeDirty` (memos) or schedules it (effects) via
+//! `Runtime::notify_subscribers_directly`. Combined with lazy verification
+//! above, this is the pull-on-read / push-on-change hybrid the module docs
+//! describe: a change pushes a `MaybeDirty` mark as far as the graph goes,
+//! but nothing downstream actually recomputes until it's pulled via `get`.
+//!
 //! # Thread Safety
 //!
-//! Memos are thread-safe. The cached value and dirty state are protected
-//! by locks. However, the computation function is called with the lock held,
-//! so computations should be fast and not block.
-
+//! Memos are thread-safe. The cached value and dirty state are protected by
+//! locks, but no lock is held across the call to `compute` itself - the
+//! previous value is cloned out beforehand, and the result is written back
+//! under a fresh lock afterward - so a concurrent reader of this memo, or of
+//! any other, never blocks on this one's computation.
+//!
+//! # Storage Modes
+//!
+//! Everything above describes the default [`MemoMode::Always`] strategy.
+//! [`Memo::with_mode`] offers two others, borrowed from Salsa's query
+//! storage: `DependencyOnly` never caches the value at all (only the
+//! dependency set, so downstream tracking still works) and recomputes on
+//! every read - useful when the value is large enough that a second copy
+//! is wasteful. `Volatile` caches normally but forces the state back to
+//! `Dirty` after every read, so it never reports `Clean` - useful for a
+//! memo wrapping a non-reactive external input (a clock, an environment
+//! variable) that has no dependency to mark dirty when it changes.
+//!
+//! # Cycle Detection
+//!
+//! Not holding a lock across `compute` avoids deadlocking on the `RwLock`s,
+//! but a memo whose `compute` reads itself - directly, or transitively
+//! through another memo - would otherwise just recurse forever. Borrowed
+//! from Salsa's query machinery: a thread-local stack of memo IDs currently
+//! being computed is pushed before calling `compute` and popped after (see
+//! `EvalGuard`); if [`Memo::try_get`] is entered for an ID already on that
+//! stack, it returns [`CycleError`] instead of recursing. [`Memo::get`] is
+//! the infallible convenience wrapper - it panics on a cycle - for callers
+//! that don't expect one and don't want to thread a `Result` through.
+//!
+//! # Avoiding Lost Updates
+//!
+//! Not holding a lock across `compute` (see "Thread Safety" above) opens a
+//! narrower race: `recompute` and `verify` both finish by separately
+//! re-acquiring the dirty-state lock to write `Clean`, after doing work that
+//! didn't hold it. If a concurrent `mark_dirty` or `mark_maybe_dirty` lands
+//! in that gap, a naive "just write `Clean`" would silently clobber it,
+//! losing the dirty mark entirely. [`MemoStatus`] closes this by pairing the
+//! state with an epoch that every dirty mark bumps: `recompute`/`verify`
+//! snapshot the epoch before doing their work and only commit `Clean` if it's
+//! unchanged when they're done, otherwise they leave the state as whatever
+//! the racing dirty mark set it to. The actual `RwLock` type this is built
+//! on comes from [`super::sync`], which swaps in `loom`'s mocked
+//! synchronization primitives under `cfg(loom)` so `tests/loom_memo.rs` can
+//! exhaustively check this invariant across interleavings instead of relying
+//! on chance.
+
+use std::cell::RefCell;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::{Arc, RwLock};
+use std::sync::Arc;
 use std::collections::HashSet;
 use std::fmt::Debug;
 
-use super::context::ReactiveContext;
+use super::context::{MemoDependency, ReactiveContext};
+use super::runtime::{Reactive, ReactiveHandle, Runtime};
 use super::subscriber::SubscriberId;
+use super::sync::RwLock;
 
 /// Counter for generating unique memo IDs.
 static MEMO_ID_COUNTER: AtomicU64 = AtomicU64::new(0);
@@ -46,6 +125,62 @@ fn next_memo_id() -> u64 {
     MEMO_ID_COUNTER.fetch_add(1, Ordering::Relaxed)
 }
 
+thread_local! {
+    /// IDs of memos currently in the middle of [`Memo::recompute`] on this
+    /// thread, innermost last - see "Cycle Detection" in the module docs.
+    static EVAL_STACK: RefCell<Vec<u64>> = RefCell::new(Vec::new());
+}
+
+/// Pushes a memo ID onto [`EVAL_STACK`] for the duration of its `compute`
+/// call, popping it on drop (including on panic, so a `compute` that
+/// unwinds never leaves a phantom entry behind).
+struct EvalGuard {
+    memo_id: u64,
+}
+
+impl EvalGuard {
+    fn enter(memo_id: u64) -> Self {
+        EVAL_STACK.with(|stack| stack.borrow_mut().push(memo_id));
+        Self { memo_id }
+    }
+}
+
+impl Drop for EvalGuard {
+    fn drop(&mut self) {
+        EVAL_STACK.with(|stack| {
+            let popped = stack.borrow_mut().pop();
+            debug_assert_eq!(
+                popped,
+                Some(self.memo_id),
+                "EvalGuard mismatch: memo eval stack popped out of order"
+            );
+        });
+    }
+}
+
+/// Returned by [`Memo::try_get`] when reading this memo would recurse into
+/// its own computation - directly (`compute` reads this same memo) or
+/// transitively (through another memo that, eventually, reads this one).
+/// Recursing would either deadlock on this memo's locks or loop forever, so
+/// this makes the cycle observable instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CycleError {
+    /// The ID of the memo whose computation this read re-entered.
+    pub memo_id: u64,
+}
+
+impl std::fmt::Display for CycleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "memo {} was read while already being computed (dependency cycle)",
+            self.memo_id
+        )
+    }
+}
+
+impl std::error::Error for CycleError {}
+
 /// Dirty state for a memo.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MemoState {
@@ -59,6 +194,127 @@ pub enum MemoState {
     Dirty,
 }
 
+/// Storage strategy for a memo, borrowed from Salsa's query storage modes.
+///
+/// The default, [`MemoMode::Always`], is what [`Memo::new`] and
+/// [`Memo::new_with_prev`] use: cache the value and the dependency set, and
+/// only recompute when lazy verification says a dependency actually
+/// changed. The other two modes trade that caching away for different
+/// reasons - see [`Memo::with_mode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoMode {
+    /// Cache the computed value as well as the dependency set. Recomputes
+    /// only when verification finds a dependency actually changed.
+    Always,
+
+    /// Track the dependency set (so downstream memos/effects still see
+    /// change propagation), but never cache the computed value itself -
+    /// [`Memo::has_value`] stays `false` forever, and every [`Memo::get`]
+    /// re-runs `compute`. Useful for large derived values where holding a
+    /// second copy around is wasteful and recomputation is cheap.
+    DependencyOnly,
+
+    /// Never considered `Clean`: the state is forced back to `Dirty` after
+    /// every read, so every [`Memo::get`] re-runs `compute`, but (unlike
+    /// `DependencyOnly`) the result is still cached in between reads.
+    /// Intended for memos that wrap non-reactive external inputs - a clock,
+    /// an environment variable - where there's no dependency to mark dirty
+    /// on change, so the memo must always assume it might have changed.
+    Volatile,
+}
+
+/// [`MemoState`] paired with a generation counter, guarded by a single lock
+/// so a dirty mark and a recompute's settle-to-`Clean` step can never race
+/// each other into a lost update - see "Avoiding Lost Updates" in the module
+/// docs and `tests/loom_memo.rs`.
+#[derive(Debug, Clone, Copy)]
+struct MemoStatus {
+    state: MemoState,
+
+    /// Bumped by every [`Memo::mark_dirty`] call, and by
+    /// [`MemoInner::mark_maybe_dirty`] when it changes `state`. A recompute or
+    /// `verify` snapshots this before doing its (lock-free) work and only
+    /// commits `Clean` if the epoch is still the same by the time it
+    /// re-acquires the lock - if not, a dirty mark landed mid-flight and
+    /// wins instead.
+    epoch: u64,
+}
+
+/// The state shared by every clone of a [`Memo`], and the type actually
+/// registered with the [`Runtime`] as a [`Reactive`].
+struct MemoInner<T>
+where
+    T: Clone + Send + Sync + PartialEq + 'static,
+{
+    /// The subscriber ID used for dependency tracking.
+    subscriber_id: SubscriberId,
+
+    /// The computation function. Takes the previous cached value (`None` on
+    /// the first run) so Leptos-style incremental derivations - diffing,
+    /// accumulation, reusing an allocation - can avoid rebuilding from
+    /// scratch. See [`Memo::new_with_prev`].
+    compute: Box<dyn Fn(Option<&T>) -> T + Send + Sync>,
+
+    /// Storage strategy - see [`MemoMode`].
+    mode: MemoMode,
+
+    /// The cached value (None if never computed, or if `mode` is
+    /// [`MemoMode::DependencyOnly`], in which case it's always None).
+    value: RwLock<Option<T>>,
+
+    /// Current dirty state plus the epoch that guards it - see
+    /// [`MemoStatus`].
+    status: RwLock<MemoStatus>,
+
+    /// Signal IDs that this memo depends on.
+    /// Updated each time the memo recomputes.
+    dependencies: RwLock<HashSet<u64>>,
+
+    /// Upstream memos read during the last recompute - the memo-of-memo
+    /// counterpart to `dependencies` above, consulted by `verify` so a
+    /// memo-of-memo chain doesn't go stale - see [`MemoDependency`].
+    memo_dependencies: RwLock<Vec<Arc<dyn MemoDependency>>>,
+
+    /// Subscriber IDs that depend on this memo.
+    dependents: RwLock<HashSet<SubscriberId>>,
+
+    /// The global revision (see [`Runtime::current_revision`]) this memo was
+    /// last confirmed valid at, via either a recompute or a successful
+    /// [`Memo::verify`].
+    verified_at: RwLock<u64>,
+
+    /// The global revision at which this memo's value last actually changed.
+    /// Only advances on a recompute that produced a value unequal to the
+    /// previous one - see "Lazy Verification" above.
+    changed_at: RwLock<u64>,
+}
+
+impl<T> Reactive for MemoInner<T>
+where
+    T: Clone + Send + Sync + PartialEq + 'static,
+{
+    fn subscriber_id(&self) -> SubscriberId {
+        self.subscriber_id
+    }
+
+    fn mark_maybe_dirty(&self) {
+        let mut status = self.status.write().expect("status lock poisoned");
+        if status.state == MemoState::Clean {
+            status.state = MemoState::MaybeDirty;
+            status.epoch = status.epoch.wrapping_add(1);
+        }
+    }
+
+    fn schedule(&self) {
+        // Memos are lazy - there's nothing to run eagerly. `mark_maybe_dirty`
+        // is all a dependent notification needs; the next `get` recomputes.
+    }
+
+    fn is_eager(&self) -> bool {
+        false
+    }
+}
+
 /// A cached derived value that recomputes only when dependencies change.
 ///
 /// # Type Parameters
@@ -74,24 +330,14 @@ where
     /// Unique identifier for this memo.
     id: u64,
 
-    /// The subscriber ID used for dependency tracking.
-    subscriber_id: SubscriberId,
+    /// State shared by every clone of this memo - also the object registered
+    /// with the [`Runtime`] as a [`Reactive`].
+    inner: Arc<MemoInner<T>>,
 
-    /// The computation function.
-    compute: Arc<dyn Fn() -> T + Send + Sync>,
-
-    /// The cached value (None if never computed).
-    value: Arc<RwLock<Option<T>>>,
-
-    /// Current dirty state.
-    state: Arc<RwLock<MemoState>>,
-
-    /// Signal IDs that this memo depends on.
-    /// Updated each time the memo recomputes.
-    dependencies: Arc<RwLock<HashSet<u64>>>,
-
-    /// Subscriber IDs that depend on this memo.
-    dependents: Arc<RwLock<HashSet<SubscriberId>>>,
+    /// Keeps this memo's runtime registration alive for as long as any clone
+    /// of this `Memo` is held - see the identical field on
+    /// [`Effect`](super::effect::Effect) for why this lives outside `inner`.
+    runtime_handle: Arc<ReactiveHandle>,
 }
 
 impl<T> Memo<T>
@@ -105,14 +351,63 @@ where
     where
         F: Fn() -> T + Send + Sync + 'static,
     {
+        Self::new_with_prev(move |_prev| compute())
+    }
+
+    /// Create a new memo whose computation also receives the previously
+    /// cached value (`None` on the first run, or if the memo has never
+    /// produced a value yet).
+    ///
+    /// Useful for incremental derivations that want to diff against, or
+    /// reuse an allocation from, the prior result rather than rebuilding it
+    /// from scratch every time.
+    pub fn new_with_prev<F>(compute: F) -> Self
+    where
+        F: Fn(Option<&T>) -> T + Send + Sync + 'static,
+    {
+        Self::with_mode_and_prev(compute, MemoMode::Always)
+    }
+
+    /// Create a new memo using a non-default [`MemoMode`] storage strategy.
+    ///
+    /// See [`MemoMode`] for what each mode changes about `get`/`recompute`.
+    pub fn with_mode<F>(compute: F, mode: MemoMode) -> Self
+    where
+        F: Fn() -> T + Send + Sync + 'static,
+    {
+        Self::with_mode_and_prev(move |_prev| compute(), mode)
+    }
+
+    /// Create a new memo using a non-default [`MemoMode`] storage strategy,
+    /// whose computation also receives the previously cached value - see
+    /// [`Self::new_with_prev`]. Note that under [`MemoMode::DependencyOnly`]
+    /// a value is never cached, so `prev` is always `None`.
+    pub fn with_mode_and_prev<F>(compute: F, mode: MemoMode) -> Self
+    where
+        F: Fn(Option<&T>) -> T + Send + Sync + 'static,
+    {
+        let inner = Arc::new(MemoInner {
+            subscriber_id: SubscriberId::new(),
+            compute: Box::new(compute),
+            mode,
+            value: RwLock::new(None),
+            status: RwLock::new(MemoStatus {
+                state: MemoState::Dirty,
+                epoch: 0,
+            }),
+            dependencies: RwLock::new(HashSet::new()),
+            memo_dependencies: RwLock::new(Vec::new()),
+            dependents: RwLock::new(HashSet::new()),
+            verified_at: RwLock::new(0),
+            changed_at: RwLock::new(0),
+        });
+
+        let runtime_handle = Arc::new(Runtime::register(Arc::clone(&inner) as Arc<dyn Reactive>));
+
         Self {
             id: next_memo_id(),
-            subscriber_id: SubscriberId::new(),
-            compute: Arc::new(compute),
-            value: Arc::new(RwLock::new(None)),
-            state: Arc::new(RwLock::new(MemoState::Dirty)),
-            dependencies: Arc::new(RwLock::new(HashSet::new())),
-            dependents: Arc::new(RwLock::new(HashSet::new())),
+            inner,
+            runtime_handle,
         }
     }
 
@@ -123,68 +418,200 @@ where
 
     /// Get the subscriber ID for this memo.
     pub fn subscriber_id(&self) -> SubscriberId {
-        self.subscriber_id
+        self.inner.subscriber_id
     }
 
     /// Get the current value, recomputing if necessary.
     ///
-    /// This is the main entry point for reading a memo's value.
+    /// This is the infallible convenience wrapper around [`Self::try_get`]
+    /// for callers that don't expect a dependency cycle and don't want to
+    /// thread a `Result` through. Panics if one is detected - see "Cycle
+    /// Detection" in the module docs.
     pub fn get(&self) -> T {
+        self.try_get().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Get the current value, recomputing if necessary, detecting a
+    /// dependency cycle instead of deadlocking or recursing forever.
+    ///
+    /// Returns [`CycleError`] if this memo's own computation is already in
+    /// progress further up the call stack on this thread - i.e. this memo
+    /// reads itself, directly or transitively through another memo.
+    pub fn try_get(&self) -> Result<T, CycleError> {
+        if EVAL_STACK.with(|stack| stack.borrow().contains(&self.id)) {
+            return Err(CycleError { memo_id: self.id });
+        }
+
         // If we're inside a reactive context, track this memo as a dependency
+        // - as a plain subscriber (for `dependent_count`), as a
+        // computation-to-computation edge so this memo changing propagates
+        // past it rather than stopping here, and as a `MemoDependency` so
+        // the reader's own `verify` (if it's a memo) can check this memo's
+        // `changed_at` too - see "Lazy Verification" in the module docs.
         if ReactiveContext::is_active() {
             if let Some(current_subscriber) = ReactiveContext::current_subscriber() {
-                self.dependents
+                self.inner
+                    .dependents
                     .write()
                     .expect("dependents lock poisoned")
                     .insert(current_subscriber);
+                Runtime::add_subscriber_dependency(self.inner.subscriber_id, current_subscriber);
+                ReactiveContext::track_memo_dependency(Arc::new(self.clone()));
             }
         }
 
         // Check if we need to recompute
-        let state = *self.state.read().expect("state lock poisoned");
+        let state = self.inner.status.read().expect("status lock poisoned").state;
 
-        match state {
+        let value = match state {
             MemoState::Clean => {
                 // Value is up-to-date, return cached
-                self.value
+                self.inner
+                    .value
                     .read()
                     .expect("value lock poisoned")
                     .clone()
                     .expect("clean memo should have a value")
             }
-            MemoState::MaybeDirty | MemoState::Dirty => {
-                // Need to recompute
+            MemoState::Dirty => {
+                // Known stale - no point verifying, just recompute.
                 self.recompute()
             }
+            MemoState::MaybeDirty => {
+                // A dependency changed, but maybe not in a way that actually
+                // affects us - try to verify before paying for a recompute.
+                if self.verify() {
+                    self.inner
+                        .value
+                        .read()
+                        .expect("value lock poisoned")
+                        .clone()
+                        .expect("verified memo should have a value")
+                } else {
+                    self.recompute()
+                }
+            }
+        };
+
+        Ok(value)
+    }
+
+    /// Try to confirm a `MaybeDirty` memo is still valid without recomputing.
+    ///
+    /// Compares each dependency's last-changed revision against
+    /// `verified_at`: if every one is unchanged since then, this memo is
+    /// "green" even though something marked it maybe-dirty. On success, also
+    /// transitions the memo to `Clean` and advances `verified_at` to the
+    /// current revision.
+    ///
+    /// Checks both signal dependencies and upstream memo dependencies
+    /// (`memo_dependencies`, populated from [`ReactiveContext::get_memo_dependencies`]):
+    /// each upstream memo is resolved via [`MemoDependency::resolve_changed_at`],
+    /// which itself lazily verifies or recomputes that memo first - so a
+    /// chain of memos-of-memos is walked transitively, not just one hop, and
+    /// a change deep in the chain is never missed just because the memo
+    /// directly in between was never itself re-read.
+    ///
+    /// Returns `true` if verification succeeded (no recompute needed), or
+    /// `false` if the caller must fall back to [`Self::recompute`].
+    ///
+    /// The epoch snapshotted up front guards against a `mark_dirty`/
+    /// `mark_maybe_dirty` landing while this check runs without holding the
+    /// status lock - see "Avoiding Lost Updates" in the module docs. If the
+    /// epoch moved, something raced this verification, so it's treated as
+    /// failed even if the dependency comparison itself said otherwise.
+    fn verify(&self) -> bool {
+        let start_epoch = self.inner.status.read().expect("status lock poisoned").epoch;
+
+        let verified_at = *self
+            .inner
+            .verified_at
+            .read()
+            .expect("verified_at lock poisoned");
+
+        let dependencies = self
+            .inner
+            .dependencies
+            .read()
+            .expect("dependencies lock poisoned")
+            .clone();
+
+        let memo_dependencies = self
+            .inner
+            .memo_dependencies
+            .read()
+            .expect("memo_dependencies lock poisoned")
+            .clone();
+
+        let still_valid = dependencies
+            .iter()
+            .all(|signal_id| Runtime::signal_changed_at(*signal_id) <= verified_at)
+            && memo_dependencies
+                .iter()
+                .all(|dep| dep.resolve_changed_at() <= verified_at);
+
+        if still_valid {
+            let mut status = self.inner.status.write().expect("status lock poisoned");
+            if status.epoch == start_epoch {
+                status.state = MemoState::Clean;
+                drop(status);
+                *self.inner.verified_at.write().expect("verified_at lock poisoned") =
+                    Runtime::current_revision();
+                return true;
+            }
+            return false;
         }
+
+        still_valid
     }
 
     /// Mark the memo as potentially needing recomputation.
     ///
     /// Called when a dependency changes.
     pub fn mark_maybe_dirty(&self) {
-        let mut state = self.state.write().expect("state lock poisoned");
-        if *state == MemoState::Clean {
-            *state = MemoState::MaybeDirty;
-        }
+        self.inner.mark_maybe_dirty();
     }
 
     /// Mark the memo as definitely needing recomputation.
     pub fn mark_dirty(&self) {
-        let mut state = self.state.write().expect("state lock poisoned");
-        *state = MemoState::Dirty;
+        let mut status = self.inner.status.write().expect("status lock poisoned");
+        status.state = MemoState::Dirty;
+        status.epoch = status.epoch.wrapping_add(1);
     }
 
     /// Recompute the memo's value.
     ///
     /// This runs the computation function within a reactive context to
-    /// track dependencies.
+    /// track dependencies. No lock is held across the call to `compute`
+    /// itself: the previous value is cloned out up front and that lock is
+    /// dropped before `compute` runs, under the guard of an [`EvalGuard`]
+    /// that makes a self-read detectable instead of deadlocking on a lock
+    /// this same call already holds - see "Cycle Detection" in the module
+    /// docs.
     fn recompute(&self) -> T {
         // Enter a reactive context to track dependencies
-        let _ctx = ReactiveContext::enter(self.subscriber_id);
+        let _ctx = ReactiveContext::enter(self.inner.subscriber_id);
+
+        // Snapshot the epoch before doing any lock-free work below, so the
+        // final state write can tell whether a concurrent dirty mark raced
+        // it - see "Avoiding Lost Updates" in the module docs.
+        let start_epoch = self.inner.status.read().expect("status lock poisoned").epoch;
 
-        // Run the computation
-        let new_value = (self.compute)();
+        let prev = self
+            .inner
+            .value
+            .read()
+            .expect("value lock poisoned")
+            .clone();
+
+        // Run the computation, handing it the previously cached value (if
+        // any) so incremental computations can diff against or reuse it.
+        // No lock is held here - a concurrent reader of this memo, or of
+        // any other, never blocks on this call.
+        let new_value = {
+            let _guard = EvalGuard::enter(self.id);
+            (self.inner.compute)(prev.as_ref())
+        };
 
         // Get the dependencies that were accessed during computation
         let new_deps: HashSet<u64> = ReactiveContext::get_dependencies()
@@ -192,19 +619,67 @@ where
             .collect();
 
         // Update our dependency set
-        *self.dependencies.write().expect("dependencies lock poisoned") = new_deps;
-
-        // Check if value actually changed
-        let value_changed = {
-            let current = self.value.read().expect("value lock poisoned");
-            current.as_ref() != Some(&new_value)
+        *self.inner.dependencies.write().expect("dependencies lock poisoned") = new_deps;
+
+        // Same, but for upstream memos read during this computation - see
+        // `MemoDependency`.
+        let new_memo_deps = ReactiveContext::get_memo_dependencies();
+        *self
+            .inner
+            .memo_dependencies
+            .write()
+            .expect("memo_dependencies lock poisoned") = new_memo_deps;
+
+        // Check if value actually changed, against the snapshot taken before
+        // `compute` ran (on this thread nothing else can have written `value`
+        // in between - only `recompute` does that, and this memo's own
+        // recursive recompute is exactly what `EvalGuard` rules out above; a
+        // concurrent recompute on another thread can have).
+        //
+        // Under `DependencyOnly` nothing is ever cached, so there's no
+        // previous value to compare against - conservatively assume it
+        // changed every time, since there's no way to tell otherwise.
+        let value_changed = match self.inner.mode {
+            MemoMode::DependencyOnly => true,
+            MemoMode::Always | MemoMode::Volatile => prev.as_ref() != Some(&new_value),
         };
 
-        // Update cached value
-        *self.value.write().expect("value lock poisoned") = Some(new_value.clone());
+        // Update cached value - skipped entirely under `DependencyOnly`, so
+        // `has_value` stays false and the next read re-runs `compute`.
+        if self.inner.mode != MemoMode::DependencyOnly {
+            *self.inner.value.write().expect("value lock poisoned") = Some(new_value.clone());
+        }
 
-        // Mark as clean
-        *self.state.write().expect("state lock poisoned") = MemoState::Clean;
+        // Always and Volatile both still want verification bookkeeping so
+        // `changed_at`/`verified_at` stay meaningful to anything reading
+        // them directly, but only `Always` is ever allowed to settle into
+        // `Clean` - `DependencyOnly` and `Volatile` force a fresh recompute
+        // on every subsequent read.
+        //
+        // `Always` only commits `Clean` if the epoch hasn't moved since
+        // `start_epoch` was snapshotted above - if it has, a concurrent
+        // dirty mark landed while `compute` was running (which holds no
+        // lock), and that mark must win instead of being silently
+        // overwritten back to `Clean`. The other two modes always want
+        // `Dirty` regardless, so there's no epoch to protect there.
+        {
+            let mut status = self.inner.status.write().expect("status lock poisoned");
+            status.state = match self.inner.mode {
+                MemoMode::Always if status.epoch == start_epoch => MemoState::Clean,
+                MemoMode::Always => status.state,
+                MemoMode::DependencyOnly | MemoMode::Volatile => MemoState::Dirty,
+            };
+        }
+
+        // We're valid as of right now regardless of whether the value
+        // changed - but only advance `changed_at` if it did. Leaving it
+        // behind when the value is unchanged (backdating) is what lets a
+        // downstream dependent short-circuit too - see "Lazy Verification".
+        let now = Runtime::current_revision();
+        *self.inner.verified_at.write().expect("verified_at lock poisoned") = now;
+        if value_changed {
+            *self.inner.changed_at.write().expect("changed_at lock poisoned") = now;
+        }
 
         // If value changed, notify dependents
         if value_changed {
@@ -214,23 +689,32 @@ where
         new_value
     }
 
-    /// Notify all dependents that this memo's value might have changed.
+    /// Notify all dependents that this memo's value changed: marks every
+    /// dependent memo `MaybeDirty` and schedules every dependent effect, via
+    /// the same `Runtime` push path a signal write uses - see "Propagation"
+    /// in the module docs.
     fn notify_dependents(&self) {
-        // In a full implementation, this would trigger the reactive system
-        // to mark dependent memos/effects as maybe-dirty
-        //
-        // For now, we just track the dependents.
-        // The integration with the scheduler will be added next.
+        let dependents: Vec<SubscriberId> = self
+            .inner
+            .dependents
+            .read()
+            .expect("dependents lock poisoned")
+            .iter()
+            .copied()
+            .collect();
+
+        Runtime::notify_subscribers_directly(dependents);
     }
 
     /// Get the current dirty state.
     pub fn state(&self) -> MemoState {
-        *self.state.read().expect("state lock poisoned")
+        self.inner.status.read().expect("status lock poisoned").state
     }
 
     /// Get the number of dependents.
     pub fn dependent_count(&self) -> usize {
-        self.dependents
+        self.inner
+            .dependents
             .read()
             .expect("dependents lock poisoned")
             .len()
@@ -238,11 +722,41 @@ where
 
     /// Check if the memo has a cached value.
     pub fn has_value(&self) -> bool {
-        self.value
+        self.inner
+            .value
             .read()
             .expect("value lock poisoned")
             .is_some()
     }
+
+    /// The revision this memo was last confirmed valid at (via a recompute
+    /// or a successful [`Self::verify`]).
+    pub fn verified_at(&self) -> u64 {
+        *self.inner.verified_at.read().expect("verified_at lock poisoned")
+    }
+
+    /// The revision at which this memo's value last actually changed under
+    /// [`PartialEq`] - see "Lazy Verification" in the module docs.
+    pub fn changed_at(&self) -> u64 {
+        *self.inner.changed_at.read().expect("changed_at lock poisoned")
+    }
+}
+
+impl<T> MemoDependency for Memo<T>
+where
+    T: Clone + Send + Sync + PartialEq + 'static,
+{
+    fn subscriber_id(&self) -> SubscriberId {
+        self.inner.subscriber_id
+    }
+
+    fn resolve_changed_at(&self) -> u64 {
+        // `get` lazily verifies or recomputes this memo exactly as a direct
+        // caller would - so a chain of memos-of-memos resolves transitively,
+        // not just one hop, before the revision comparison below happens.
+        self.get();
+        self.changed_at()
+    }
 }
 
 impl<T> Clone for Memo<T>
@@ -252,12 +766,8 @@ where
     fn clone(&self) -> Self {
         Self {
             id: self.id,
-            subscriber_id: self.subscriber_id,
-            compute: Arc::clone(&self.compute),
-            value: Arc::clone(&self.value),
-            state: Arc::clone(&self.state),
-            dependencies: Arc::clone(&self.dependencies),
-            dependents: Arc::clone(&self.dependents),
+            inner: Arc::clone(&self.inner),
+            runtime_handle: Arc::clone(&self.runtime_handle),
         }
     }
 }
@@ -283,6 +793,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use super::super::{Effect, Signal};
     use std::sync::atomic::{AtomicI32, Ordering};
 
     #[test]
@@ -356,27 +867,105 @@ mod tests {
     }
 
     #[test]
-    fn memo_recomputes_when_maybe_dirty() {
+    fn memo_verifies_without_recomputing_when_maybe_dirty_but_no_dependency_changed() {
         let call_count = Arc::new(AtomicI32::new(0));
         let call_count_clone = call_count.clone();
 
+        // No signal reads, so this memo has no dependencies at all - being
+        // marked maybe-dirty (e.g. spuriously, or by something unrelated)
+        // should verify clean with zero extra calls to `compute`.
         let memo = Memo::new(move || {
             call_count_clone.fetch_add(1, Ordering::SeqCst);
             42
         });
 
-        // First access
         assert_eq!(memo.get(), 42);
         assert_eq!(call_count.load(Ordering::SeqCst), 1);
 
-        // Mark as maybe dirty
         memo.mark_maybe_dirty();
 
-        // Next access should recompute (in full implementation, would check deps)
         assert_eq!(memo.get(), 42);
+        assert_eq!(call_count.load(Ordering::SeqCst), 1);
+        assert_eq!(memo.state(), MemoState::Clean);
+    }
+
+    #[test]
+    fn memo_recomputes_when_maybe_dirty_and_a_dependency_actually_changed() {
+        let call_count = Arc::new(AtomicI32::new(0));
+        let call_count_clone = call_count.clone();
+
+        let signal = Signal::new(1);
+        let signal_clone = signal.clone();
+
+        let memo = Memo::new(move || {
+            call_count_clone.fetch_add(1, Ordering::SeqCst);
+            signal_clone.get()
+        });
+
+        assert_eq!(memo.get(), 1);
+        assert_eq!(call_count.load(Ordering::SeqCst), 1);
+
+        signal.set(2);
+        memo.mark_maybe_dirty();
+
+        assert_eq!(memo.get(), 2);
         assert_eq!(call_count.load(Ordering::SeqCst), 2);
     }
 
+    #[test]
+    fn memo_backdates_changed_at_when_recompute_yields_an_equal_value() {
+        let signal = Signal::new(1);
+        let signal_clone = signal.clone();
+
+        // Always collapses to the same parity regardless of the signal's
+        // exact value.
+        let memo = Memo::new(move || signal_clone.get() % 2);
+
+        assert_eq!(memo.get(), 1);
+
+        let changed_at_after_first = memo.changed_at();
+
+        // New value, but the same parity - the memo's own output is
+        // unchanged, so `changed_at` should stay put (backdated) even
+        // though a real recompute ran.
+        signal.set(3);
+        memo.mark_maybe_dirty();
+
+        assert_eq!(memo.get(), 1);
+
+        assert_eq!(memo.changed_at(), changed_at_after_first);
+        assert!(memo.verified_at() >= changed_at_after_first);
+    }
+
+    #[test]
+    fn new_with_prev_receives_none_then_the_last_cached_value() {
+        let seen_prev = Arc::new(RwLock::new(Vec::new()));
+        let seen_prev_clone = seen_prev.clone();
+
+        let counter = Arc::new(AtomicI32::new(0));
+        let counter_clone = counter.clone();
+
+        let memo = Memo::new_with_prev(move |prev: Option<&i32>| {
+            seen_prev_clone.write().unwrap().push(prev.copied());
+            counter_clone.load(Ordering::SeqCst)
+        });
+
+        assert_eq!(memo.get(), 0);
+
+        counter.store(10, Ordering::SeqCst);
+        memo.mark_dirty();
+        assert_eq!(memo.get(), 10);
+
+        counter.store(20, Ordering::SeqCst);
+        memo.mark_dirty();
+        assert_eq!(memo.get(), 20);
+
+        assert_eq!(
+            *seen_prev.read().unwrap(),
+            vec![None, Some(0), Some(10)]
+        );
+    }
+
     #[test]
     fn memo_clone_shares_state() {
         let memo1 = Memo::new(|| 42);
@@ -419,4 +1008,221 @@ mod tests {
         memo.get();
         assert_eq!(memo.state(), MemoState::Clean);
     }
+
+    #[test]
+    fn memo_changing_marks_a_dependent_memo_maybe_dirty_without_recomputing_it() {
+        // upstream -> downstream, with downstream read once to register the
+        // subscriber-dependency edge. upstream changing should push a
+        // `MaybeDirty` mark to downstream, but not force a recompute until
+        // downstream is actually read again.
+        let signal = Signal::new(1);
+        let signal_clone = signal.clone();
+        let upstream = Memo::new(move || signal_clone.get());
+
+        let downstream_calls = Arc::new(AtomicI32::new(0));
+        let downstream_calls_clone = downstream_calls.clone();
+        let upstream_clone = upstream.clone();
+        let downstream = Memo::new(move || {
+            downstream_calls_clone.fetch_add(1, Ordering::SeqCst);
+            upstream_clone.get() * 10
+        });
+
+        assert_eq!(downstream.get(), 10);
+        assert_eq!(downstream_calls.load(Ordering::SeqCst), 1);
+
+        signal.set(2);
+
+        assert_eq!(downstream.state(), MemoState::MaybeDirty);
+        assert_eq!(downstream_calls.load(Ordering::SeqCst), 1);
+
+        assert_eq!(downstream.get(), 20);
+        assert_eq!(downstream_calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn diamond_signal_triggers_each_downstream_memo_exactly_once() {
+        // signal -> memo_a, memo_b -> effect (reading both). Changing the
+        // signal should recompute each memo exactly once when the effect
+        // reads them, not twice because of the diamond shape.
+        let signal = Signal::new(1);
+
+        let a_calls = Arc::new(AtomicI32::new(0));
+        let a_calls_clone = a_calls.clone();
+        let signal_a = signal.clone();
+        let memo_a = Memo::new(move || {
+            a_calls_clone.fetch_add(1, Ordering::SeqCst);
+            signal_a.get() + 1
+        });
+
+        let b_calls = Arc::new(AtomicI32::new(0));
+        let b_calls_clone = b_calls.clone();
+        let signal_b = signal.clone();
+        let memo_b = Memo::new(move || {
+            b_calls_clone.fetch_add(1, Ordering::SeqCst);
+            signal_b.get() + 2
+        });
+
+        let effect_runs = Arc::new(AtomicI32::new(0));
+        let effect_runs_clone = effect_runs.clone();
+        let memo_a_clone = memo_a.clone();
+        let memo_b_clone = memo_b.clone();
+        let _effect = Effect::new(move || {
+            effect_runs_clone.fetch_add(1, Ordering::SeqCst);
+            let _ = memo_a_clone.get() + memo_b_clone.get();
+        });
+
+        assert_eq!(effect_runs.load(Ordering::SeqCst), 1);
+        assert_eq!(a_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(b_calls.load(Ordering::SeqCst), 1);
+
+        signal.set(5);
+
+        assert_eq!(effect_runs.load(Ordering::SeqCst), 2);
+        assert_eq!(a_calls.load(Ordering::SeqCst), 2);
+        assert_eq!(b_calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn try_get_detects_a_memo_reading_itself() {
+        // `Arc<RwLock<Option<Memo<i32>>>>` gives the compute closure a way to
+        // refer to the memo it's inside of, which doesn't exist yet at the
+        // point the closure is constructed. The cycle is detected at the
+        // re-entrant call from inside `compute`, not at the outer call that
+        // triggered the first recompute - that outer call still completes
+        // normally, using whatever the closure decided to fall back to.
+        let observed_cycle: Arc<RwLock<Option<CycleError>>> = Arc::new(RwLock::new(None));
+        let observed_cycle_clone = observed_cycle.clone();
+
+        let self_ref: Arc<RwLock<Option<Memo<i32>>>> = Arc::new(RwLock::new(None));
+        let self_ref_clone = self_ref.clone();
+
+        let memo = Memo::new(move || {
+            let this = self_ref_clone.read().unwrap().clone().unwrap();
+            match this.try_get() {
+                Ok(value) => value + 1,
+                Err(err) => {
+                    *observed_cycle_clone.write().unwrap() = Some(err);
+                    -1
+                }
+            }
+        });
+        *self_ref.write().unwrap() = Some(memo.clone());
+
+        assert_eq!(memo.get(), -1);
+        assert_eq!(
+            *observed_cycle.read().unwrap(),
+            Some(CycleError { memo_id: memo.id() })
+        );
+    }
+
+    #[test]
+    fn get_panics_on_a_self_referencing_cycle() {
+        let self_ref: Arc<RwLock<Option<Memo<i32>>>> = Arc::new(RwLock::new(None));
+        let self_ref_clone = self_ref.clone();
+
+        let memo = Memo::new(move || {
+            let this = self_ref_clone.read().unwrap().clone().unwrap();
+            this.get()
+        });
+        *self_ref.write().unwrap() = Some(memo.clone());
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| memo.get()));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn try_get_detects_a_transitive_two_memo_cycle() {
+        // memo_a reads memo_b, memo_b reads memo_a back - neither exists
+        // yet when the other's closure is built, so both go through the
+        // same `Arc<RwLock<Option<Memo<i32>>>>` forward-reference trick.
+        // The cycle surfaces at the innermost re-entrant call (memo_b's
+        // read of memo_a), so that's what's captured and asserted on.
+        let observed_cycle: Arc<RwLock<Option<CycleError>>> = Arc::new(RwLock::new(None));
+        let observed_cycle_clone = observed_cycle.clone();
+
+        let b_ref: Arc<RwLock<Option<Memo<i32>>>> = Arc::new(RwLock::new(None));
+        let b_ref_clone = b_ref.clone();
+
+        let a_ref: Arc<RwLock<Option<Memo<i32>>>> = Arc::new(RwLock::new(None));
+        let a_ref_clone = a_ref.clone();
+
+        let memo_a = Memo::new(move || {
+            let b = b_ref_clone.read().unwrap().clone().unwrap();
+            b.get() + 1
+        });
+        *a_ref.write().unwrap() = Some(memo_a.clone());
+
+        let memo_b = Memo::new(move || {
+            let a = a_ref_clone.read().unwrap().clone().unwrap();
+            match a.try_get() {
+                Ok(value) => value + 1,
+                Err(err) => {
+                    *observed_cycle_clone.write().unwrap() = Some(err);
+                    -1
+                }
+            }
+        });
+        *b_ref.write().unwrap() = Some(memo_b.clone());
+
+        assert_eq!(memo_a.get(), 0);
+        assert_eq!(
+            *observed_cycle.read().unwrap(),
+            Some(CycleError { memo_id: memo_a.id() })
+        );
+    }
+
+    #[test]
+    fn dependency_only_memo_never_caches_a_value_and_recomputes_every_read() {
+        let call_count = Arc::new(AtomicI32::new(0));
+        let call_count_clone = call_count.clone();
+
+        let signal = Signal::new(1);
+        let signal_clone = signal.clone();
+
+        let memo = Memo::with_mode(
+            move || {
+                call_count_clone.fetch_add(1, Ordering::SeqCst);
+                signal_clone.get() * 2
+            },
+            MemoMode::DependencyOnly,
+        );
+
+        assert_eq!(memo.get(), 2);
+        assert_eq!(call_count.load(Ordering::SeqCst), 1);
+        assert!(!memo.has_value());
+
+        // No dependency changed, but DependencyOnly recomputes regardless.
+        assert_eq!(memo.get(), 2);
+        assert_eq!(call_count.load(Ordering::SeqCst), 2);
+        assert!(!memo.has_value());
+
+        signal.set(5);
+        assert_eq!(memo.get(), 10);
+        assert_eq!(call_count.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn volatile_memo_caches_but_never_reports_clean() {
+        let call_count = Arc::new(AtomicI32::new(0));
+        let call_count_clone = call_count.clone();
+
+        let memo = Memo::with_mode(
+            move || {
+                call_count_clone.fetch_add(1, Ordering::SeqCst);
+                42
+            },
+            MemoMode::Volatile,
+        );
+
+        assert_eq!(memo.get(), 42);
+        assert_eq!(call_count.load(Ordering::SeqCst), 1);
+        assert!(memo.has_value());
+        assert_eq!(memo.state(), MemoState::Dirty);
+
+        // Unlike Always, re-reading recomputes even with no signal involved
+        // at all, because the state never settles into Clean.
+        assert_eq!(memo.get(), 42);
+        assert_eq!(call_count.load(Ordering::SeqCst), 2);
+        assert_eq!(memo.state(), MemoState::Dirty);
+    }
 }
@@ -18,6 +18,13 @@
 //! one of its dependencies changes. Memos are useful for expensive computations
 //! that should not be repeated unnecessarily.
 //!
+//! ## Async Memos
+//!
+//! An [`AsyncMemo`] is a [`Memo`]-like derived value computed by a spawned
+//! `Future` rather than a synchronous closure. Reading it never blocks - it
+//! reports `Pending` until the spawned computation resolves, then `Ready`
+//! with the value.
+//!
 //! ## Effects
 //!
 //! An Effect is a side-effecting computation that runs whenever its dependencies
@@ -37,12 +44,21 @@ mod signal;
 mod context;
 mod subscriber;
 mod memo;
+mod async_memo;
 mod effect;
 mod runtime;
+mod stream;
+mod scope;
+mod selector;
+mod sync;
 
-pub use signal::{Signal, PySignal};
+pub use signal::{Signal, PySignal, PySignalStream};
 pub use context::ReactiveContext;
 pub use subscriber::{Subscriber, SubscriberId};
-pub use memo::{Memo, MemoState};
-pub use effect::Effect;
+pub use memo::{CycleError, Memo, MemoMode, MemoState};
+pub use async_memo::{AsyncMemo, AsyncMemoState, Spawner};
+pub use effect::{Effect, on_cleanup};
 pub use runtime::{Runtime, Reactive, ReactiveHandle};
+pub use stream::SignalStream;
+pub use scope::ScopeHandle;
+pub use selector::{Selector, PySelector};
@@ -22,12 +22,71 @@
 //! The runtime uses thread-local storage for the reactive context and
 //! a global registry for cross-thread signal access. This allows signals
 //! to be shared across threads while keeping the common case fast.
-
-use std::sync::{Arc, RwLock, Weak, OnceLock};
-use std::collections::HashMap;
+//!
+//! # Glitch-Free Propagation
+//!
+//! A naive implementation that runs every dependent the instant a signal
+//! changes can run the same node more than once per change, or run it before
+//! one of its other inputs has settled (the "diamond problem": a signal
+//! feeds two memos that both feed one effect, so the effect would otherwise
+//! run twice, once with a stale intermediate value).
+//!
+//! We avoid this with a two-phase update, similar in spirit to
+//! [`graph::UpdateScheduler`](crate::graph::UpdateScheduler)'s topological
+//! sort:
+//!
+//! 1. **Mark** - walk the dependency graph from the changed signal, marking
+//!    every transitive dependent "maybe dirty" and computing its `height`
+//!    (the length of the longest dependency chain from a signal to that
+//!    node). A node reached by more than one path takes the maximum height
+//!    seen, so it's never scheduled before a slower-to-settle input.
+//! 2. **Sweep** - drain the marked nodes in ascending height order, running
+//!    each at most once (the mark phase dedupes by subscriber ID, so a node
+//!    reachable via both memos in the diamond above is only queued once).
+//!
+//! [`Runtime::batch`] defers the sweep until its closure returns, so many
+//! `Signal::set` calls coalesce into a single propagation instead of one
+//! sweep per call. The dedup is the same `PENDING` map the non-batched path
+//! already uses - there's no separate "batched effect queue": an effect
+//! scheduled twice during one batch is still just one entry in `PENDING`,
+//! flushed once, in height order rather than raw registration order (which
+//! is what actually keeps the flush glitch-free). `BATCH_DEPTH` is
+//! thread-local, so independent batches on different threads never defer or
+//! flush on each other's behalf. Effects that themselves write a signal while
+//! the flush is running schedule more `PENDING` entries, which `sweep`'s
+//! (now-empty) batch depth causes to drain immediately, in a nested call -
+//! this is the "follow-up pass" that keeps convergence glitch-free even when
+//! an effect's own execution triggers more effects.
+//!
+//! One more wrinkle: a [`Memo`](crate::reactive::Memo) that recomputes while
+//! being *pulled* by one of its own dependents (the common case - an effect
+//! reads it, finds it stale, and that recompute calls back out to
+//! `notify_dependents`) must not re-schedule that same dependent. `sweep`
+//! guards against this with [`ReactiveContext::is_subscriber_active`]: a
+//! subscriber already executing somewhere on this thread's call stack will
+//! observe the fresh value from its own in-flight read before it returns, so
+//! scheduling it again would at best re-run it for nothing and at worst
+//! reenter a lock (like `Effect`'s run mutex) it's still holding.
+//!
+//! # Revision Counter
+//!
+//! Alongside mark/sweep, the runtime keeps a single global, monotonically
+//! increasing revision number (see [`Runtime::bump_revision`]), bumped once
+//! per *actual* signal change (so `Signal::set_if_changed` skipping a
+//! no-op write also skips the bump). Every signal records the revision it
+//! last changed at in [`SIGNAL_CHANGED_AT`]. This is what lets [`Memo`](
+//! crate::reactive::Memo) implement Salsa-style lazy verification: a
+//! `MaybeDirty` memo can compare its dependencies' recorded revisions
+//! against its own `verified_at` instead of unconditionally recomputing.
+
+use std::cell::Cell;
+use std::sync::{Arc, Mutex, RwLock, Weak, OnceLock};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::collections::{HashMap, VecDeque};
 
 use super::subscriber::SubscriberId;
 use super::context::ReactiveContext;
+use super::scope::{self, Disposable, Owner, ScopeHandle};
 
 /// A trait for types that can be notified when dependencies change.
 pub trait Reactive: Send + Sync {
@@ -57,6 +116,23 @@ impl Drop for ReactiveHandle {
     }
 }
 
+/// Wraps a raw [`SubscriberId`] so it can be owned by the scope tree (see
+/// `reactive::scope`) alongside nested scopes and `Effect`'s own internal
+/// owner.
+///
+/// Unlike [`ReactiveHandle`], disposing this doesn't rely on a drop: a scope
+/// may outlive or be disposed independently of whichever `ReactiveHandle` the
+/// caller is holding, so this only ever triggers `Runtime::unregister`, which
+/// is idempotent - a `Reactive` unregistered this way and then again via its
+/// handle's own `Drop` is unregistered twice, the second a no-op.
+struct OwnedSubscriber(SubscriberId);
+
+impl Disposable for OwnedSubscriber {
+    fn dispose(&self) {
+        Runtime::unregister(self.0);
+    }
+}
+
 /// The global reactive runtime.
 ///
 /// This is a singleton that manages all reactive values in the application.
@@ -67,6 +143,42 @@ pub struct Runtime;
 static REGISTRY: OnceLock<RwLock<HashMap<SubscriberId, Weak<dyn Reactive>>>> = OnceLock::new();
 static SIGNAL_SUBSCRIBERS: OnceLock<RwLock<HashMap<u64, Vec<SubscriberId>>>> = OnceLock::new();
 
+/// Weak counterpart to `SIGNAL_SUBSCRIBERS`: subscribers that can read a
+/// signal's current value without being marked maybe-dirty when it changes.
+/// Kept in its own map, rather than tagging entries in `SIGNAL_SUBSCRIBERS`,
+/// so `mark`'s lookup stays a plain "every strong subscriber" scan with no
+/// per-entry filtering.
+static WEAK_SIGNAL_SUBSCRIBERS: OnceLock<RwLock<HashMap<u64, Vec<SubscriberId>>>> = OnceLock::new();
+
+/// Direct "computation depends on computation" edges, e.g. an effect that
+/// reads a memo rather than a signal directly. Separate from
+/// `SIGNAL_SUBSCRIBERS` because signal IDs and subscriber IDs are different
+/// ID spaces.
+static SUBSCRIBER_DEPENDENTS: OnceLock<RwLock<HashMap<SubscriberId, Vec<SubscriberId>>>> = OnceLock::new();
+
+thread_local! {
+    /// Number of nested [`Runtime::batch`] calls currently active *on this
+    /// thread*. The sweep phase only runs when this drops back to zero.
+    ///
+    /// Thread-local rather than a shared atomic: a batch on one thread has no
+    /// business deferring (or being deferred by) a batch on another, and
+    /// `PENDING`/`SIGNAL_SUBSCRIBERS` etc. are already the cross-thread
+    /// shared state that actually needs coordinating.
+    static BATCH_DEPTH: Cell<usize> = Cell::new(0);
+}
+
+/// Nodes marked dirty by the current (possibly batched) update, keyed by
+/// subscriber ID with the highest height seen so far. Draining this map at
+/// sweep time is what guarantees at-most-once execution per batch.
+static PENDING: OnceLock<Mutex<HashMap<SubscriberId, usize>>> = OnceLock::new();
+
+/// Global monotonic revision counter - see "Revision Counter" above.
+static REVISION: AtomicU64 = AtomicU64::new(0);
+
+/// Per-signal "revision at which this signal last actually changed", keyed by
+/// signal ID. A signal not present here has never changed since creation.
+static SIGNAL_CHANGED_AT: OnceLock<RwLock<HashMap<u64, u64>>> = OnceLock::new();
+
 fn get_registry() -> &'static RwLock<HashMap<SubscriberId, Weak<dyn Reactive>>> {
     REGISTRY.get_or_init(|| RwLock::new(HashMap::new()))
 }
@@ -75,18 +187,41 @@ fn get_signal_subscribers() -> &'static RwLock<HashMap<u64, Vec<SubscriberId>>>
     SIGNAL_SUBSCRIBERS.get_or_init(|| RwLock::new(HashMap::new()))
 }
 
+fn get_weak_signal_subscribers() -> &'static RwLock<HashMap<u64, Vec<SubscriberId>>> {
+    WEAK_SIGNAL_SUBSCRIBERS.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+fn get_subscriber_dependents() -> &'static RwLock<HashMap<SubscriberId, Vec<SubscriberId>>> {
+    SUBSCRIBER_DEPENDENTS.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+fn get_pending() -> &'static Mutex<HashMap<SubscriberId, usize>> {
+    PENDING.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn get_signal_changed_at() -> &'static RwLock<HashMap<u64, u64>> {
+    SIGNAL_CHANGED_AT.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
 impl Runtime {
     /// Register a reactive value with the runtime.
     ///
-    /// Returns a handle that unregisters the value when dropped.
+    /// Returns a handle that unregisters the value when dropped. If a scope
+    /// is currently active (see [`create_scope`](Self::create_scope)), the
+    /// reactive value also becomes one of that scope's owned items, so
+    /// disposing the scope unregisters it (and purges its signal-subscriber
+    /// and subscriber-dependent entries) even if the `ReactiveHandle` itself
+    /// is still held elsewhere.
     pub fn register(reactive: Arc<dyn Reactive>) -> ReactiveHandle {
         let id = reactive.subscriber_id();
-        
+
         get_registry()
             .write()
             .expect("registry lock poisoned")
             .insert(id, Arc::downgrade(&reactive));
-        
+
+        Owner::register_with_current(Arc::new(OwnedSubscriber(id)) as Arc<dyn Disposable>);
+
         ReactiveHandle { subscriber_id: id }
     }
 
@@ -97,21 +232,52 @@ impl Runtime {
             .expect("registry lock poisoned")
             .remove(&id);
         
-        // Also remove from signal subscribers
+        // Also remove from signal subscribers, strong and weak.
         let mut subscribers = get_signal_subscribers()
             .write()
             .expect("signal_subscribers lock poisoned");
-        
+
         for subs in subscribers.values_mut() {
             subs.retain(|s| *s != id);
         }
+        drop(subscribers);
+
+        let mut weak_subscribers = get_weak_signal_subscribers()
+            .write()
+            .expect("weak_signal_subscribers lock poisoned");
+
+        for subs in weak_subscribers.values_mut() {
+            subs.retain(|s| *s != id);
+        }
+        drop(weak_subscribers);
+
+        // Also remove from subscriber-to-subscriber edges
+        let mut subscriber_dependents = get_subscriber_dependents()
+            .write()
+            .expect("subscriber_dependents lock poisoned");
+        subscriber_dependents.remove(&id);
+        for deps in subscriber_dependents.values_mut() {
+            deps.retain(|s| *s != id);
+        }
     }
 
     /// Record that a subscriber depends on a signal.
     ///
     /// Called automatically when a signal is read within a reactive context.
-    pub fn add_dependency(signal_id: u64, subscriber_id: SubscriberId) {
-        get_signal_subscribers()
+    ///
+    /// `weak` selects a "read-if-present" dependency: `subscriber_id` can
+    /// still read the signal's value, but the signal changing alone never
+    /// marks it maybe-dirty - only a *strong* dependency elsewhere doing so
+    /// will. Mirrors `UpdateScheduler::add_weak_edge` in the computational
+    /// graph.
+    pub fn add_dependency(signal_id: u64, subscriber_id: SubscriberId, weak: bool) {
+        let subscribers = if weak {
+            get_weak_signal_subscribers()
+        } else {
+            get_signal_subscribers()
+        };
+
+        subscribers
             .write()
             .expect("signal_subscribers lock poisoned")
             .entry(signal_id)
@@ -119,64 +285,229 @@ impl Runtime {
             .push(subscriber_id);
     }
 
-    /// Remove all dependencies for a subscriber.
+    /// Record that `dependent` depends on `dependency` (another computation,
+    /// not a signal directly) - for example an effect that reads a memo.
+    ///
+    /// This lets the mark phase propagate dirtiness (and height) past a
+    /// computation's own subscribers, rather than stopping at the signals
+    /// it reads directly.
+    pub fn add_subscriber_dependency(dependency: SubscriberId, dependent: SubscriberId) {
+        get_subscriber_dependents()
+            .write()
+            .expect("subscriber_dependents lock poisoned")
+            .entry(dependency)
+            .or_insert_with(Vec::new)
+            .push(dependent);
+    }
+
+    /// Remove all dependencies (strong and weak) for a subscriber.
     ///
     /// Called before re-running a computation to clear stale dependencies.
     pub fn clear_dependencies(subscriber_id: SubscriberId) {
         let mut subscribers = get_signal_subscribers()
             .write()
             .expect("signal_subscribers lock poisoned");
-        
+
         for subs in subscribers.values_mut() {
             subs.retain(|s| *s != subscriber_id);
         }
+        drop(subscribers);
+
+        let mut weak_subscribers = get_weak_signal_subscribers()
+            .write()
+            .expect("weak_signal_subscribers lock poisoned");
+
+        for subs in weak_subscribers.values_mut() {
+            subs.retain(|s| *s != subscriber_id);
+        }
     }
 
     /// Notify all subscribers that a signal changed.
     ///
-    /// This is the core update propagation mechanism.
+    /// This is the core update propagation mechanism. It marks every
+    /// transitive dependent dirty (see the module docs for the mark/sweep
+    /// design), then runs the sweep immediately unless we're inside a
+    /// [`batch`](Self::batch), in which case the sweep is deferred until the
+    /// outermost batch closure returns.
     pub fn notify_signal_change(signal_id: u64) {
-        // Get subscribers for this signal
-        let subscriber_ids = {
-            let subscribers = get_signal_subscribers()
-                .read()
-                .expect("signal_subscribers lock poisoned");
-            
-            subscribers
-                .get(&signal_id)
-                .cloned()
-                .unwrap_or_default()
-        };
+        Self::record_signal_changed(signal_id, Self::bump_revision());
+
+        Self::mark(signal_id);
+
+        if !Self::is_batching() {
+            Self::sweep();
+        }
+    }
+
+    /// Advance and return the global revision counter.
+    ///
+    /// Called once per *actual* signal change (see the module docs'
+    /// "Revision Counter" section) - `Signal::set_if_changed` skipping a
+    /// no-op write skips this too, since nothing downstream needs
+    /// re-verifying.
+    pub fn bump_revision() -> u64 {
+        REVISION.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// Read the global revision counter without advancing it.
+    pub fn current_revision() -> u64 {
+        REVISION.load(Ordering::SeqCst)
+    }
+
+    /// Record that `signal_id` last actually changed at `revision`.
+    fn record_signal_changed(signal_id: u64, revision: u64) {
+        get_signal_changed_at()
+            .write()
+            .expect("signal_changed_at lock poisoned")
+            .insert(signal_id, revision);
+    }
+
+    /// The revision at which `signal_id` last actually changed, or `0` if
+    /// it never has (including if it's not a signal ID at all - callers
+    /// verifying a dependency's freshness treat "never changed" the same
+    /// way regardless of why).
+    pub fn signal_changed_at(signal_id: u64) -> u64 {
+        *get_signal_changed_at()
+            .read()
+            .expect("signal_changed_at lock poisoned")
+            .get(&signal_id)
+            .unwrap_or(&0)
+    }
+
+    /// Defer propagation until `f` returns, coalescing every `Signal::set`
+    /// made inside `f` (including ones from nested `batch` calls) into a
+    /// single sweep.
+    ///
+    /// Without this, a diamond graph (one signal feeding two memos that both
+    /// feed one effect) would run the effect once per `set` rather than once
+    /// per logical update.
+    pub fn batch<F, R>(f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        BATCH_DEPTH.with(|depth| depth.set(depth.get() + 1));
+        let result = f();
+        let remaining = BATCH_DEPTH.with(|depth| {
+            let remaining = depth.get() - 1;
+            depth.set(remaining);
+            remaining
+        });
+
+        if remaining == 0 {
+            Self::sweep();
+        }
+
+        result
+    }
+
+    fn is_batching() -> bool {
+        BATCH_DEPTH.with(|depth| depth.get() > 0)
+    }
+
+    /// Mark and (unless inside a [`batch`](Self::batch)) sweep exactly
+    /// `subscriber_ids`, skipping the signal-ID lookup
+    /// [`notify_signal_change`](Self::notify_signal_change) does.
+    ///
+    /// For primitives that track their own, finer-grained subscriber sets
+    /// than "every dependent of one signal ID" - e.g. `Selector`, which only
+    /// wants to notify the subscribers of the key that was deselected and
+    /// the one that was selected.
+    pub(crate) fn notify_subscribers_directly(subscriber_ids: impl IntoIterator<Item = SubscriberId>) {
+        Self::mark_subscribers(subscriber_ids);
+
+        if !Self::is_batching() {
+            Self::sweep();
+        }
+    }
+
+    /// Walk the dependency graph from `signal_id`, marking every transitive
+    /// dependent "maybe dirty" and recording its height (the longest
+    /// dependency chain seen so far from a signal to that node).
+    ///
+    /// Heights are relaxed rather than fixed on first visit: if a node is
+    /// reached again via a longer path, its height (and therefore its
+    /// position in the sweep) is raised, so it never runs before a
+    /// still-unsettled input on that longer path.
+    fn mark(signal_id: u64) {
+        let direct_subscribers = get_signal_subscribers()
+            .read()
+            .expect("signal_subscribers lock poisoned")
+            .get(&signal_id)
+            .cloned()
+            .unwrap_or_default();
 
-        if subscriber_ids.is_empty() {
+        if direct_subscribers.is_empty() {
             return;
         }
 
-        // Get the actual reactive values
-        let registry = get_registry()
+        Self::mark_subscribers(direct_subscribers);
+    }
+
+    /// Seed the mark worklist directly from a set of subscriber IDs, rather
+    /// than looking them up from a signal ID via `SIGNAL_SUBSCRIBERS`.
+    ///
+    /// Used by [`mark`](Self::mark) for the common signal-change case, and
+    /// by [`notify_subscribers_directly`](Self::notify_subscribers_directly)
+    /// for primitives (like `Selector`) that already know precisely which
+    /// subscribers are affected without going through a single signal ID.
+    fn mark_subscribers(subscriber_ids: impl IntoIterator<Item = SubscriberId>) {
+        let registry = get_registry().read().expect("registry lock poisoned");
+        let subscriber_dependents = get_subscriber_dependents()
             .read()
-            .expect("registry lock poisoned");
-
-        let mut effects_to_run = Vec::new();
-
-        for sub_id in subscriber_ids {
-            if let Some(weak) = registry.get(&sub_id) {
-                if let Some(reactive) = weak.upgrade() {
-                    // Mark as maybe dirty
-                    reactive.mark_maybe_dirty();
-                    
-                    // If it's an eager reactive (effect), schedule it
-                    if reactive.is_eager() {
-                        effects_to_run.push(reactive);
-                    }
+            .expect("subscriber_dependents lock poisoned");
+        let mut pending = get_pending().lock().expect("pending lock poisoned");
+
+        let mut queue: VecDeque<(SubscriberId, usize)> =
+            subscriber_ids.into_iter().map(|id| (id, 0)).collect();
+
+        while let Some((id, height)) = queue.pop_front() {
+            let is_new_max = pending.get(&id).map_or(true, |&current| height > current);
+            if !is_new_max {
+                continue;
+            }
+            pending.insert(id, height);
+
+            if let Some(reactive) = registry.get(&id).and_then(Weak::upgrade) {
+                reactive.mark_maybe_dirty();
+            }
+
+            if let Some(dependents) = subscriber_dependents.get(&id) {
+                for &dependent in dependents {
+                    queue.push_back((dependent, height + 1));
                 }
             }
         }
+    }
+
+    /// Drain every node marked by the current batch, in ascending height
+    /// order, and schedule each eager (effect) node at most once.
+    fn sweep() {
+        let mut ordered: Vec<(SubscriberId, usize)> = {
+            let mut pending = get_pending().lock().expect("pending lock poisoned");
+            pending.drain().collect()
+        };
 
-        // Release the registry lock before running effects
-        drop(registry);
+        if ordered.is_empty() {
+            return;
+        }
 
-        // Run scheduled effects
+        ordered.sort_by_key(|&(_, height)| height);
+
+        let effects_to_run: Vec<Arc<dyn Reactive>> = {
+            let registry = get_registry().read().expect("registry lock poisoned");
+            ordered
+                .into_iter()
+                .filter_map(|(id, _)| registry.get(&id).and_then(Weak::upgrade))
+                .filter(|reactive| {
+                    reactive.is_eager()
+                        && !ReactiveContext::is_subscriber_active(reactive.subscriber_id())
+                })
+                .collect()
+        };
+
+        // Run effects in height order with the registry lock released, so an
+        // effect that itself calls `Signal::set` doesn't deadlock against
+        // this read lock.
         for effect in effects_to_run {
             effect.schedule();
         }
@@ -191,6 +522,20 @@ impl Runtime {
     pub fn is_tracking() -> bool {
         ReactiveContext::is_active()
     }
+
+    /// Run `f` with a fresh scope active, returning a handle that disposes
+    /// every effect (and nested scope) created while `f` ran.
+    ///
+    /// Nest calls to mirror component/subtree lifetimes: disposing an outer
+    /// scope disposes everything created inside it, depth-first. See
+    /// `reactive::scope` for how an individual `Effect` additionally disposes
+    /// its own previous generation of children on each re-run.
+    pub fn create_scope<F>(f: F) -> ScopeHandle
+    where
+        F: FnOnce(),
+    {
+        scope::create_scope(f)
+    }
 }
 
 #[cfg(test)]
@@ -203,6 +548,10 @@ mod tests {
         dirty: AtomicBool,
         scheduled: AtomicI32,
         eager: bool,
+        /// Run once, the first time `schedule` is called - lets a test
+        /// simulate an effect that itself writes a signal (and so schedules
+        /// more effects) while it runs.
+        on_schedule: Mutex<Option<Box<dyn FnMut() + Send>>>,
     }
 
     impl MockReactive {
@@ -212,6 +561,17 @@ mod tests {
                 dirty: AtomicBool::new(false),
                 scheduled: AtomicI32::new(0),
                 eager,
+                on_schedule: Mutex::new(None),
+            })
+        }
+
+        fn with_on_schedule(eager: bool, on_schedule: impl FnMut() + Send + 'static) -> Arc<Self> {
+            Arc::new(Self {
+                id: SubscriberId::new(),
+                dirty: AtomicBool::new(false),
+                scheduled: AtomicI32::new(0),
+                eager,
+                on_schedule: Mutex::new(Some(Box::new(on_schedule))),
             })
         }
     }
@@ -227,6 +587,15 @@ mod tests {
 
         fn schedule(&self) {
             self.scheduled.fetch_add(1, Ordering::SeqCst);
+
+            let callback = self
+                .on_schedule
+                .lock()
+                .expect("on_schedule lock poisoned")
+                .take();
+            if let Some(mut callback) = callback {
+                callback();
+            }
         }
 
         fn is_eager(&self) -> bool {
@@ -263,8 +632,8 @@ mod tests {
         let _effect_handle = Runtime::register(effect.clone());
         
         // Add dependencies
-        Runtime::add_dependency(42, memo_id);
-        Runtime::add_dependency(42, effect_id);
+        Runtime::add_dependency(42, memo_id, false);
+        Runtime::add_dependency(42, effect_id, false);
         
         // Notify change
         Runtime::notify_signal_change(42);
@@ -286,7 +655,7 @@ mod tests {
         let _handle = Runtime::register(reactive.clone());
         
         // Add dependency
-        Runtime::add_dependency(100, id);
+        Runtime::add_dependency(100, id, false);
         
         // Verify it exists
         {
@@ -296,11 +665,184 @@ mod tests {
         
         // Clear
         Runtime::clear_dependencies(id);
-        
+
         // Verify it's gone
         {
             let subs = get_signal_subscribers().read().unwrap();
             assert!(!subs.get(&100).map(|v| v.contains(&id)).unwrap_or(false));
         }
     }
+
+    #[test]
+    fn diamond_dependent_runs_at_most_once() {
+        // signal 200 -> memo_a, memo_b -> effect. Without height-based
+        // dedup the effect would be queued twice (once per memo path).
+        let memo_a = MockReactive::new(false);
+        let memo_b = MockReactive::new(false);
+        let effect = MockReactive::new(true);
+
+        let _a_handle = Runtime::register(memo_a.clone());
+        let _b_handle = Runtime::register(memo_b.clone());
+        let _effect_handle = Runtime::register(effect.clone());
+
+        Runtime::add_dependency(200, memo_a.id, false);
+        Runtime::add_dependency(200, memo_b.id, false);
+        Runtime::add_subscriber_dependency(memo_a.id, effect.id);
+        Runtime::add_subscriber_dependency(memo_b.id, effect.id);
+
+        Runtime::notify_signal_change(200);
+
+        assert!(memo_a.dirty.load(Ordering::SeqCst));
+        assert!(memo_b.dirty.load(Ordering::SeqCst));
+        assert_eq!(effect.scheduled.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn weak_dependency_is_not_marked_dirty_on_signal_change() {
+        let strong_memo = MockReactive::new(false);
+        let weak_memo = MockReactive::new(false);
+
+        let _strong_handle = Runtime::register(strong_memo.clone());
+        let _weak_handle = Runtime::register(weak_memo.clone());
+
+        Runtime::add_dependency(500, strong_memo.id, false);
+        Runtime::add_dependency(500, weak_memo.id, true);
+
+        Runtime::notify_signal_change(500);
+
+        assert!(strong_memo.dirty.load(Ordering::SeqCst));
+        assert!(!weak_memo.dirty.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn disposing_a_scope_unregisters_reactives_created_inside_it() {
+        let memo = MockReactive::new(false);
+        let id = memo.id;
+        // Held for the test's duration, the way a real owner (e.g. a `Memo`)
+        // would keep its own handle alive - the scope must still unregister
+        // `memo` on dispose even though this handle hasn't been dropped.
+        let mut memo_handle = None;
+
+        let handle = Runtime::create_scope(|| {
+            memo_handle = Some(Runtime::register(memo.clone()));
+            Runtime::add_dependency(400, id, false);
+        });
+
+        assert!(get_registry().read().unwrap().contains_key(&id));
+        assert!(get_signal_subscribers()
+            .read()
+            .unwrap()
+            .get(&400)
+            .map(|v| v.contains(&id))
+            .unwrap_or(false));
+
+        handle.dispose();
+
+        assert!(!get_registry().read().unwrap().contains_key(&id));
+        assert!(!get_signal_subscribers()
+            .read()
+            .unwrap()
+            .get(&400)
+            .map(|v| v.contains(&id))
+            .unwrap_or(false));
+
+        // The original handle is still alive; dropping it now is a harmless
+        // no-op unregister, not a double-free.
+        drop(memo_handle);
+    }
+
+    #[test]
+    fn batch_coalesces_multiple_signal_changes_into_one_sweep() {
+        let effect = MockReactive::new(true);
+        let _handle = Runtime::register(effect.clone());
+
+        Runtime::add_dependency(300, effect.id, false);
+        Runtime::add_dependency(301, effect.id, false);
+
+        Runtime::batch(|| {
+            Runtime::notify_signal_change(300);
+            Runtime::notify_signal_change(301);
+            // Not swept yet - we're still inside the batch.
+            assert_eq!(effect.scheduled.load(Ordering::SeqCst), 0);
+        });
+
+        // Two signals changed, but the effect only runs once per batch.
+        assert_eq!(effect.scheduled.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn nested_batches_coalesce_into_the_outermost_flush() {
+        let effect = MockReactive::new(true);
+        let _handle = Runtime::register(effect.clone());
+        Runtime::add_dependency(310, effect.id, false);
+
+        Runtime::batch(|| {
+            Runtime::batch(|| {
+                Runtime::notify_signal_change(310);
+                assert_eq!(effect.scheduled.load(Ordering::SeqCst), 0);
+            });
+            // Still inside the outer batch - the inner one closing doesn't
+            // flush on its own.
+            assert_eq!(effect.scheduled.load(Ordering::SeqCst), 0);
+        });
+
+        assert_eq!(effect.scheduled.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn batch_is_scoped_per_thread() {
+        use std::sync::Barrier;
+        use std::thread;
+
+        let effect = MockReactive::new(true);
+        let _handle = Runtime::register(effect.clone());
+        Runtime::add_dependency(320, effect.id, false);
+
+        // Synchronize so the background thread's batch is provably still
+        // open while the main thread's write lands - if `BATCH_DEPTH` were
+        // shared, the main thread's write would get deferred by the
+        // background thread's still-open batch and never flush here.
+        let inside_batch = Arc::new(Barrier::new(2));
+        let may_close_batch = Arc::new(Barrier::new(2));
+        let inside_batch_clone = inside_batch.clone();
+        let may_close_batch_clone = may_close_batch.clone();
+
+        let handle = thread::spawn(move || {
+            Runtime::batch(|| {
+                inside_batch_clone.wait();
+                may_close_batch_clone.wait();
+            });
+        });
+
+        inside_batch.wait();
+        Runtime::notify_signal_change(320);
+        assert_eq!(effect.scheduled.load(Ordering::SeqCst), 1);
+
+        may_close_batch.wait();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn effects_scheduled_during_flush_are_swept_in_a_follow_up_pass() {
+        // One effect that, when it runs, writes a signal that a second
+        // effect depends on - this must still run to completion within the
+        // original `batch` call, not be left dangling.
+        let second = MockReactive::new(true);
+        let second_id = second.id;
+        let _second_handle = Runtime::register(second.clone());
+        Runtime::add_dependency(331, second_id, false);
+
+        let first = MockReactive::with_on_schedule(true, move || {
+            Runtime::notify_signal_change(331);
+        });
+        let _first_handle = Runtime::register(first.clone());
+        Runtime::add_dependency(330, first.id, false);
+
+        Runtime::batch(|| {
+            Runtime::notify_signal_change(330);
+        });
+
+        assert_eq!(first.scheduled.load(Ordering::SeqCst), 1);
+        assert_eq!(second.scheduled.load(Ordering::SeqCst), 1);
+    }
 }
@@ -0,0 +1,249 @@
+//! Owner/Scope Tree
+//!
+//! `Effect::dispose()` exists, but nothing calls it automatically: an effect
+//! created inside another effect's function re-runs (and is recreated) every
+//! time the outer effect re-runs, but the previous generation's subscription
+//! to `Signal::subscribers` is never torn down, so it leaks. This module
+//! borrows the Owner/Scope concept from Leptos and Sycamore to fix that.
+//!
+//! # How It Works
+//!
+//! A thread-local stack holds the currently active [`Owner`]. Anything
+//! created while an owner is active - currently, every [`Effect`](super::effect::Effect)
+//! and every reactive value passed to `Runtime::register` -
+//! registers itself as that owner's child via [`Owner::register_with_current`].
+//! Disposing an owner disposes its children depth-first (a child that is
+//! itself an owner, like an effect's internal owner, disposes its own
+//! children first through the same [`Disposable`] call).
+//!
+//! Three things use this tree:
+//!
+//! - [`Runtime::create_scope`](super::runtime::Runtime::create_scope) creates
+//!   a top-level [`ScopeHandle`] whose disposal (explicit or on drop) tears
+//!   down every effect created while it was active.
+//! - Every [`Effect`](super::effect::Effect) has its own internal owner, so
+//!   effects created during one of its executions become children of *that
+//!   execution*: the next re-run disposes them before running again,
+//!   preventing the unbounded accumulation of stale subscribers that
+//!   motivated this module.
+//! - [`Runtime::register`](super::runtime::Runtime::register) registers the
+//!   `SubscriberId` of whatever `Reactive` it's given as an owned item too
+//!   (wrapped so disposal calls `Runtime::unregister`), so a scope containing
+//!   a raw `Reactive` - not just `Effect`s - still unregisters it and purges
+//!   its signal-subscriber and subscriber-dependent entries on dispose.
+
+use std::cell::RefCell;
+use std::sync::{Arc, Mutex};
+
+thread_local! {
+    static OWNER_STACK: RefCell<Vec<Arc<Owner>>> = RefCell::new(Vec::new());
+}
+
+/// Something an [`Owner`] can dispose of.
+///
+/// Implemented by [`Effect`](super::effect::Effect), by [`Owner`] itself
+/// (so scopes can nest), and by the runtime's wrapper around a registered
+/// subscriber ID, whose disposal unregisters it.
+pub trait Disposable: Send + Sync {
+    fn dispose(&self);
+}
+
+/// Owns a set of child disposables created while it was the active scope.
+#[derive(Default)]
+pub struct Owner {
+    children: Mutex<Vec<Arc<dyn Disposable>>>,
+}
+
+impl Owner {
+    /// Create a fresh, empty owner.
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Register `child` with the owner currently active on this thread, if
+    /// any. A no-op outside of any scope/effect.
+    pub fn register_with_current(child: Arc<dyn Disposable>) {
+        OWNER_STACK.with(|stack| {
+            if let Some(owner) = stack.borrow().last() {
+                owner
+                    .children
+                    .lock()
+                    .expect("owner children lock poisoned")
+                    .push(child);
+            }
+        });
+    }
+
+    /// Dispose every child depth-first, then forget them - so an owner can
+    /// be reused for a fresh generation of children (as effects do on
+    /// re-run) rather than only ever disposed once.
+    pub fn dispose_children(&self) {
+        let children: Vec<Arc<dyn Disposable>> = {
+            let mut children = self.children.lock().expect("owner children lock poisoned");
+            children.drain(..).collect()
+        };
+        for child in children {
+            child.dispose();
+        }
+    }
+
+    /// Make this owner active for the duration of `f`, so anything
+    /// registered via [`Owner::register_with_current`] while it runs becomes
+    /// one of this owner's children.
+    pub fn with_active<F, R>(self: &Arc<Self>, f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        OWNER_STACK.with(|stack| stack.borrow_mut().push(Arc::clone(self)));
+        let result = f();
+        OWNER_STACK.with(|stack| {
+            stack.borrow_mut().pop();
+        });
+        result
+    }
+}
+
+impl Disposable for Owner {
+    fn dispose(&self) {
+        self.dispose_children();
+    }
+}
+
+/// Handle to a scope created by
+/// [`Runtime::create_scope`](super::runtime::Runtime::create_scope).
+///
+/// Disposing (explicitly, or implicitly on drop) disposes every effect - and
+/// nested scope - created while the scope was active, depth-first.
+pub struct ScopeHandle {
+    owner: Arc<Owner>,
+}
+
+impl ScopeHandle {
+    /// Dispose this scope's descendants now, rather than waiting for drop.
+    pub fn dispose(&self) {
+        self.owner.dispose_children();
+    }
+}
+
+impl Drop for ScopeHandle {
+    fn drop(&mut self) {
+        self.owner.dispose_children();
+    }
+}
+
+/// Run `f` with a fresh owner active, nested inside the current owner (if
+/// any) so that disposing a parent scope also disposes this one.
+pub fn create_scope<F>(f: F) -> ScopeHandle
+where
+    F: FnOnce(),
+{
+    let owner = Owner::new();
+    Owner::register_with_current(Arc::clone(&owner) as Arc<dyn Disposable>);
+    owner.with_active(f);
+    ScopeHandle { owner }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reactive::effect::Effect;
+    use std::sync::atomic::{AtomicI32, Ordering};
+
+    #[test]
+    fn disposing_a_scope_disposes_effects_created_inside_it() {
+        let run_count = Arc::new(AtomicI32::new(0));
+        let run_count_clone = run_count.clone();
+        let mut captured: Option<Effect> = None;
+
+        let handle = create_scope(|| {
+            captured = Some(Effect::new(move || {
+                run_count_clone.fetch_add(1, Ordering::SeqCst);
+            }));
+        });
+        let effect = captured.unwrap();
+
+        assert_eq!(run_count.load(Ordering::SeqCst), 1);
+        assert!(!effect.is_disposed());
+
+        handle.dispose();
+        assert!(effect.is_disposed());
+
+        // A disposed effect no longer runs.
+        effect.schedule();
+        assert_eq!(run_count.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn effects_created_outside_any_scope_are_unaffected() {
+        let effect = Effect::new(|| {});
+        assert!(!effect.is_disposed());
+        // No scope was ever created, so there's nothing to dispose it.
+    }
+
+    #[test]
+    fn disposing_a_scope_disposes_nested_child_effects_depth_first() {
+        // A scope containing an effect that itself creates a child effect:
+        // disposing the scope should tear down the grandchild too, since the
+        // parent effect's own owner disposes *its* children when disposed.
+        let grandchild_run_count = Arc::new(AtomicI32::new(0));
+        let grandchild: Arc<Mutex<Option<Effect>>> = Arc::new(Mutex::new(None));
+
+        let handle = {
+            let grandchild_run_count = grandchild_run_count.clone();
+            let grandchild = grandchild.clone();
+            create_scope(move || {
+                let grandchild_run_count = grandchild_run_count.clone();
+                let grandchild = grandchild.clone();
+                Effect::new(move || {
+                    let grandchild_run_count = grandchild_run_count.clone();
+                    let child = Effect::new(move || {
+                        grandchild_run_count.fetch_add(1, Ordering::SeqCst);
+                    });
+                    *grandchild.lock().unwrap() = Some(child);
+                });
+            })
+        };
+
+        let grandchild = grandchild.lock().unwrap().clone().unwrap();
+        assert_eq!(grandchild_run_count.load(Ordering::SeqCst), 1);
+        assert!(!grandchild.is_disposed());
+
+        handle.dispose();
+        assert!(grandchild.is_disposed());
+    }
+
+    #[test]
+    fn effect_rerun_disposes_previous_generation_of_child_effects() {
+        let child_run_count = Arc::new(AtomicI32::new(0));
+        let child_effects: Arc<Mutex<Vec<Effect>>> = Arc::new(Mutex::new(Vec::new()));
+
+        let parent_run_count = Arc::new(AtomicI32::new(0));
+        let parent = {
+            let child_run_count = child_run_count.clone();
+            let child_effects = child_effects.clone();
+            let parent_run_count = parent_run_count.clone();
+            Effect::new(move || {
+                parent_run_count.fetch_add(1, Ordering::SeqCst);
+                let child_run_count = child_run_count.clone();
+                let child = Effect::new(move || {
+                    child_run_count.fetch_add(1, Ordering::SeqCst);
+                });
+                child_effects.lock().unwrap().push(child);
+            })
+        };
+        let _ = parent;
+
+        assert_eq!(parent_run_count.load(Ordering::SeqCst), 1);
+        assert_eq!(child_run_count.load(Ordering::SeqCst), 1);
+
+        let first_child = child_effects.lock().unwrap()[0].clone();
+        assert!(!first_child.is_disposed());
+
+        // Re-running the parent should dispose the first generation's child
+        // before creating (and running) the next one.
+        parent.execute();
+        assert!(first_child.is_disposed());
+        assert_eq!(child_effects.lock().unwrap().len(), 2);
+        assert!(!child_effects.lock().unwrap()[1].is_disposed());
+    }
+}
@@ -0,0 +1,262 @@
+//! Selector Implementation
+//!
+//! A `Selector` answers "is `key` the currently selected one?" for a keyed
+//! collection, without the O(n) re-run cost of a plain `Signal`: reading
+//! `is_selected(key)` inside a memo or effect only registers that computation
+//! as a dependent of `key`'s bucket, so changing the selection from `old` to
+//! `new` notifies just the two items whose membership actually changed
+//! (`old` deselects, `new` selects) rather than every item in the list.
+//!
+//! This is the same primitive as Leptos's `selector.rs`, built on the
+//! existing `Signal`/`SubscriberId` machinery already used elsewhere in this
+//! module.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let selected = Selector::new(0);
+//!
+//! Effect::new(|| {
+//!     // Only re-runs when row 3's membership changes, not on every
+//!     // selection change elsewhere in the list.
+//!     println!("Row 3 selected: {}", selected.is_selected(&3));
+//! });
+//!
+//! selected.select(3); // notifies row 3's (and the old row's) subscribers
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::sync::{Arc, RwLock};
+
+use pyo3::prelude::*;
+
+use super::context::ReactiveContext;
+use super::runtime::Runtime;
+use super::signal::Signal;
+use super::SubscriberId;
+
+/// Tracks which subscribers care about each key's selection state, and
+/// notifies only the subscribers of the keys whose membership actually
+/// changes on `select`.
+pub struct Selector<K>
+where
+    K: Eq + Hash + Clone + Send + Sync + 'static,
+{
+    /// The currently selected key.
+    current: Signal<K>,
+
+    /// Subscribers registered against each key, populated by `is_selected`.
+    buckets: Arc<RwLock<HashMap<K, HashSet<SubscriberId>>>>,
+}
+
+impl<K> Selector<K>
+where
+    K: Eq + Hash + Clone + Send + Sync + 'static,
+{
+    /// Create a selector with `initial` as the selected key.
+    pub fn new(initial: K) -> Self {
+        Self {
+            current: Signal::new(initial),
+            buckets: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Check whether `key` is currently selected.
+    ///
+    /// If called within a reactive context, registers the caller under
+    /// `key`'s bucket only - a selection change between two other keys
+    /// won't re-run this subscriber.
+    pub fn is_selected(&self, key: &K) -> bool {
+        if ReactiveContext::is_active() {
+            if let Some(subscriber_id) = ReactiveContext::current_subscriber() {
+                self.buckets
+                    .write()
+                    .expect("buckets lock poisoned")
+                    .entry(key.clone())
+                    .or_insert_with(HashSet::new)
+                    .insert(subscriber_id);
+            }
+        }
+
+        self.current.get_untracked() == *key
+    }
+
+    /// Get the currently selected key without establishing a dependency.
+    pub fn selected_key(&self) -> K {
+        self.current.get_untracked()
+    }
+
+    /// Change the selected key.
+    ///
+    /// Only the subscribers of the previously selected key and the newly
+    /// selected key are notified - every other key's bucket is left alone.
+    pub fn select(&self, key: K) {
+        let old = self.current.get_untracked();
+        if old == key {
+            return;
+        }
+
+        self.current.set(key.clone());
+
+        let affected: Vec<SubscriberId> = {
+            let buckets = self.buckets.read().expect("buckets lock poisoned");
+            buckets
+                .get(&old)
+                .into_iter()
+                .chain(buckets.get(&key))
+                .flatten()
+                .copied()
+                .collect()
+        };
+
+        Runtime::notify_subscribers_directly(affected);
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Python Bindings
+// ----------------------------------------------------------------------------
+
+/// Python-exposed Selector, keyed by `i64` (the common case: row/item
+/// indices or IDs in a list).
+#[pyclass(name = "Selector")]
+pub struct PySelector {
+    inner: Selector<i64>,
+}
+
+#[pymethods]
+impl PySelector {
+    /// Create a selector with `initial` as the selected key.
+    #[new]
+    fn new(initial: i64) -> Self {
+        Self {
+            inner: Selector::new(initial),
+        }
+    }
+
+    /// Check whether `key` is currently selected.
+    fn is_selected(&self, key: i64) -> bool {
+        self.inner.is_selected(&key)
+    }
+
+    /// Get the currently selected key.
+    #[getter]
+    fn selected_key(&self) -> i64 {
+        self.inner.selected_key()
+    }
+
+    /// Change the selected key.
+    fn select(&self, key: i64) {
+        self.inner.select(key);
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Tests
+// ----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicI32, Ordering};
+
+    /// A minimal `Reactive` for verifying which subscribers get marked,
+    /// mirroring `runtime::tests::MockReactive`.
+    struct MockReactive {
+        id: SubscriberId,
+        marked: Arc<AtomicI32>,
+    }
+
+    impl super::super::runtime::Reactive for MockReactive {
+        fn subscriber_id(&self) -> SubscriberId {
+            self.id
+        }
+
+        fn mark_maybe_dirty(&self) {
+            self.marked.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn schedule(&self) {}
+
+        fn is_eager(&self) -> bool {
+            false
+        }
+    }
+
+    /// Returns the registered `Arc<MockReactive>` alongside its handle and
+    /// keeps it in the tuple the caller binds - `Runtime::register` only
+    /// keeps a `Weak`, so letting the last strong ref drop here would free
+    /// the mock before `select` ever got a chance to notify it.
+    fn register_mock() -> (
+        SubscriberId,
+        Arc<AtomicI32>,
+        Arc<MockReactive>,
+        super::super::runtime::ReactiveHandle,
+    ) {
+        let marked = Arc::new(AtomicI32::new(0));
+        let reactive = Arc::new(MockReactive {
+            id: SubscriberId::new(),
+            marked: marked.clone(),
+        });
+        let id = reactive.id;
+        let handle = Runtime::register(reactive.clone());
+        (id, marked, reactive, handle)
+    }
+
+    #[test]
+    fn is_selected_reflects_current_key() {
+        let selector = Selector::new(1);
+
+        assert!(selector.is_selected(&1));
+        assert!(!selector.is_selected(&2));
+
+        selector.select(2);
+
+        assert!(!selector.is_selected(&1));
+        assert!(selector.is_selected(&2));
+    }
+
+    #[test]
+    fn select_only_notifies_old_and_new_bucket_subscribers() {
+        let selector = Selector::new(1);
+
+        let (row1_id, row1_marked, _row1, _row1_handle) = register_mock();
+        let (row2_id, row2_marked, _row2, _row2_handle) = register_mock();
+        let (row3_id, row3_marked, _row3, _row3_handle) = register_mock();
+
+        // Register each mock as a subscriber of its own row by reading
+        // `is_selected` inside that row's reactive context.
+        let _ctx = ReactiveContext::enter(row1_id);
+        selector.is_selected(&1);
+        drop(_ctx);
+
+        let _ctx = ReactiveContext::enter(row2_id);
+        selector.is_selected(&2);
+        drop(_ctx);
+
+        let _ctx = ReactiveContext::enter(row3_id);
+        selector.is_selected(&3);
+        drop(_ctx);
+
+        // Selecting row 2 (from row 1) should only notify rows 1 and 2.
+        selector.select(2);
+
+        assert_eq!(row1_marked.load(Ordering::SeqCst), 1);
+        assert_eq!(row2_marked.load(Ordering::SeqCst), 1);
+        assert_eq!(row3_marked.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn selecting_the_same_key_is_a_no_op() {
+        let selector = Selector::new(1);
+        let (row1_id, row1_marked, _row1, _row1_handle) = register_mock();
+
+        let _ctx = ReactiveContext::enter(row1_id);
+        selector.is_selected(&1);
+        drop(_ctx);
+
+        selector.select(1);
+        assert_eq!(row1_marked.load(Ordering::SeqCst), 0);
+    }
+}
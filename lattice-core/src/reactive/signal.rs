@@ -25,15 +25,19 @@
 //! - A set of subscriber IDs (grows with number of dependents)
 
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
 use std::collections::HashSet;
 use std::fmt::Debug;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
 
 use pyo3::prelude::*;
 use pyo3::types::PyAny;
 
 use super::context::ReactiveContext;
 use super::runtime::Runtime;
+use super::stream::SignalStream;
 use super::SubscriberId;
 
 /// Counter for generating unique signal IDs.
@@ -117,7 +121,7 @@ where
                     .insert(subscriber_id);
                 
                 // Global tracking (for runtime-managed updates)
-                Runtime::add_dependency(self.id, subscriber_id);
+                Runtime::add_dependency(self.id, subscriber_id, false);
             }
         }
 
@@ -128,6 +132,28 @@ where
             .clone()
     }
 
+    /// Get the current value, registering a *weak* ("read-if-present")
+    /// dependency rather than a strong one.
+    ///
+    /// Unlike [`get`](Self::get), this signal changing never marks the
+    /// current subscriber maybe-dirty by itself - it only gets re-examined
+    /// if something else (a strong dependency) already dirtied it, at which
+    /// point it can still pull this signal's current value. Useful for
+    /// reading a signal without forcing recomputation on every change, or to
+    /// avoid a cycle a strong edge the other way would create.
+    pub fn get_weak(&self) -> T {
+        if ReactiveContext::is_active() {
+            if let Some(subscriber_id) = ReactiveContext::current_subscriber() {
+                Runtime::add_dependency(self.id, subscriber_id, true);
+            }
+        }
+
+        self.value
+            .read()
+            .expect("value lock poisoned")
+            .clone()
+    }
+
     /// Get the current value without tracking dependencies.
     ///
     /// Use this when you need to read the value without establishing
@@ -216,6 +242,60 @@ where
             .expect("subscriber lock poisoned")
             .len()
     }
+
+    /// Bridge this signal into a [`Stream`](futures_core::Stream) of its
+    /// values: the current value immediately, then one item per subsequent
+    /// `set`.
+    ///
+    /// Backed by an internal subscriber that's removed when the returned
+    /// stream is dropped. Useful for consuming the reactive graph from
+    /// `async`/`tokio` code that can't participate in it directly.
+    pub fn to_stream(&self) -> SignalStream<T> {
+        SignalStream::new(self)
+    }
+}
+
+impl<T> Signal<T>
+where
+    T: Clone + Send + Sync + PartialEq + 'static,
+{
+    /// Set a new value, skipping propagation entirely if it equals the
+    /// current one.
+    ///
+    /// Plain [`Signal::set`] always notifies, even when the new value is
+    /// identical to the old one - fine for types that can't be compared, but
+    /// wasteful for graphs where many writes are no-ops (e.g. clamping a
+    /// value that's already at its bound). This compares under the write
+    /// lock and only calls `notify_subscribers`/`Runtime::notify_signal_change`
+    /// when `new != old`.
+    pub fn set_if_changed(&self, value: T) {
+        let changed = {
+            let mut guard = self.value.write().expect("value lock poisoned");
+            if *guard == value {
+                false
+            } else {
+                *guard = value;
+                true
+            }
+        };
+
+        if changed {
+            self.notify_subscribers();
+            Runtime::notify_signal_change(self.id);
+        }
+    }
+
+    /// Set a new value and always notify subscribers, even if it equals the
+    /// current one.
+    ///
+    /// An escape hatch for callers that need dependents to re-run regardless
+    /// of equality (e.g. forcing a re-render after mutating something the
+    /// value's `PartialEq` impl doesn't see). Equivalent to [`Signal::set`];
+    /// provided here so code using [`Signal::set_if_changed`] as its default
+    /// setter has an explicit, discoverable way to opt out per call.
+    pub fn set_untracked_force(&self, value: T) {
+        self.set(value);
+    }
 }
 
 impl<T> Clone for Signal<T>
@@ -264,6 +344,17 @@ pub struct PySignal {
 
     /// Number of subscribers (simplified for now).
     subscriber_count: Arc<RwLock<usize>>,
+
+    /// Wakers for any `to_stream()` iterators currently awaiting a change.
+    waiters: Arc<Mutex<Vec<Waker>>>,
+}
+
+impl PySignal {
+    fn wake_waiters(&self) {
+        for waker in self.waiters.lock().expect("waiters lock poisoned").drain(..) {
+            waker.wake();
+        }
+    }
 }
 
 #[pymethods]
@@ -275,6 +366,7 @@ impl PySignal {
             id: next_signal_id(),
             value: Arc::new(RwLock::new(value)),
             subscriber_count: Arc::new(RwLock::new(0)),
+            waiters: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
@@ -288,8 +380,54 @@ impl PySignal {
     /// Set a new value.
     #[setter]
     fn set_value(&self, value: PyObject) {
-        let mut guard = self.value.write().expect("value lock poisoned");
-        *guard = value;
+        {
+            let mut guard = self.value.write().expect("value lock poisoned");
+            *guard = value;
+        }
+        self.wake_waiters();
+    }
+
+    /// Set a new value, skipping the write if it compares equal (via
+    /// Python's `__eq__`) to the current one.
+    ///
+    /// Mirrors [`Signal::set_if_changed`] for the Python-exposed signal.
+    fn set_if_changed(&self, value: PyObject, py: Python<'_>) -> PyResult<()> {
+        let changed = {
+            let mut guard = self.value.write().expect("value lock poisoned");
+            if guard.bind(py).eq(value.bind(py))? {
+                false
+            } else {
+                *guard = value;
+                true
+            }
+        };
+        if changed {
+            self.wake_waiters();
+        }
+        Ok(())
+    }
+
+    /// Set a new value unconditionally, bypassing the `set_if_changed`
+    /// equality check.
+    fn set_untracked_force(&self, value: PyObject) {
+        {
+            let mut guard = self.value.write().expect("value lock poisoned");
+            *guard = value;
+        }
+        self.wake_waiters();
+    }
+
+    /// Return an async iterator over this signal's values: the current value
+    /// immediately, then one item per subsequent set, for bridging into
+    /// `asyncio` (`async for value in signal.to_stream(): ...`).
+    ///
+    /// Mirrors [`Signal::to_stream`] for the Python-exposed signal.
+    fn to_stream(&self) -> PySignalStream {
+        PySignalStream {
+            value: Arc::clone(&self.value),
+            waiters: Arc::clone(&self.waiters),
+            yielded_initial: false,
+        }
     }
 
     /// Get the signal's unique ID.
@@ -319,6 +457,76 @@ impl PySignal {
     }
 }
 
+/// Future that resolves the next time a [`PySignal`]'s `waiters` list is
+/// woken, i.e. the next time that signal is set.
+///
+/// Registers its waker at most once; a spurious wake (another waiter's
+/// change landing before this one polls again) simply resolves this future
+/// a beat early with whatever the current value is at read time, which is
+/// the same "latest value" semantics [`PySignalStream`] already documents.
+struct WaitForChange {
+    waiters: Arc<Mutex<Vec<Waker>>>,
+    registered: bool,
+}
+
+impl Future for WaitForChange {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.registered {
+            return Poll::Ready(());
+        }
+        self.waiters
+            .lock()
+            .expect("waiters lock poisoned")
+            .push(cx.waker().clone());
+        self.registered = true;
+        Poll::Pending
+    }
+}
+
+/// Async iterator bridging a [`PySignal`]'s changes into `asyncio`, returned
+/// by [`PySignal::to_stream`]. Usable as `async for value in
+/// signal.to_stream(): ...`.
+///
+/// Unlike [`SignalStream`] on the Rust side, this doesn't queue every
+/// intermediate `set` - each `__anext__` resolves with whatever the signal's
+/// value is at that moment, which is the natural fit for `asyncio` consumers
+/// that want the latest value rather than a full change log.
+#[pyclass(name = "SignalStream")]
+pub struct PySignalStream {
+    value: Arc<RwLock<Py<PyAny>>>,
+    waiters: Arc<Mutex<Vec<Waker>>>,
+    yielded_initial: bool,
+}
+
+#[pymethods]
+impl PySignalStream {
+    fn __aiter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __anext__(&mut self, py: Python<'_>) -> PyResult<PyObject> {
+        if !self.yielded_initial {
+            self.yielded_initial = true;
+            let current = self.value.read().expect("value lock poisoned").clone_ref(py);
+            return pyo3_async_runtimes::tokio::future_into_py(py, async move { Ok(current) })
+                .map(Into::into);
+        }
+
+        let value = Arc::clone(&self.value);
+        let waiters = Arc::clone(&self.waiters);
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            WaitForChange {
+                waiters,
+                registered: false,
+            }
+            .await;
+            Python::with_gil(|py| Ok(value.read().expect("value lock poisoned").clone_ref(py)))
+        })
+        .map(Into::into)
+    }
+}
 
 // ----------------------------------------------------------------------------
 // Tests
@@ -397,6 +605,63 @@ mod tests {
         assert_eq!(signal1.get(), 100);
     }
 
+    #[test]
+    fn get_weak_reads_the_value_without_strong_tracking() {
+        let signal = Signal::new(7);
+        let subscriber_id = SubscriberId::new();
+
+        let _ctx = ReactiveContext::enter(subscriber_id);
+        assert_eq!(signal.get_weak(), 7);
+        drop(_ctx);
+
+        // `get_weak` still reads the latest value...
+        signal.set(8);
+        let _ctx = ReactiveContext::enter(subscriber_id);
+        assert_eq!(signal.get_weak(), 8);
+        drop(_ctx);
+
+        // ...but never registers with `self.subscribers`/`notifiers` (the
+        // explicit `subscribe`/`notify_subscribers` callback mechanism),
+        // since that's orthogonal to the runtime's weak/strong dependency
+        // tracking this method exercises.
+        assert_eq!(signal.subscriber_count(), 0);
+    }
+
+    #[test]
+    fn set_if_changed_skips_notification_when_value_is_equal() {
+        let signal = Signal::new(5);
+        let call_count = Arc::new(AtomicI32::new(0));
+        let call_count_clone = call_count.clone();
+
+        let subscriber_id = SubscriberId::new();
+        signal.subscribe(subscriber_id, move || {
+            call_count_clone.fetch_add(1, Ordering::SeqCst);
+        });
+
+        signal.set_if_changed(5);
+        assert_eq!(call_count.load(Ordering::SeqCst), 0);
+        assert_eq!(signal.get_untracked(), 5);
+
+        signal.set_if_changed(6);
+        assert_eq!(call_count.load(Ordering::SeqCst), 1);
+        assert_eq!(signal.get_untracked(), 6);
+    }
+
+    #[test]
+    fn set_untracked_force_notifies_even_when_value_is_equal() {
+        let signal = Signal::new(5);
+        let call_count = Arc::new(AtomicI32::new(0));
+        let call_count_clone = call_count.clone();
+
+        let subscriber_id = SubscriberId::new();
+        signal.subscribe(subscriber_id, move || {
+            call_count_clone.fetch_add(1, Ordering::SeqCst);
+        });
+
+        signal.set_untracked_force(5);
+        assert_eq!(call_count.load(Ordering::SeqCst), 1);
+    }
+
     #[test]
     fn signal_ids_are_unique() {
         let s1 = Signal::new(0);
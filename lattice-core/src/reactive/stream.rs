@@ -0,0 +1,155 @@
+//! Signal -> Stream Bridge
+//!
+//! The reactive graph is synchronous: a `Signal::set` call drives its
+//! subscribers immediately, on the calling thread. `async`/`tokio` code can't
+//! participate in that directly, so this module bridges a [`Signal`] into a
+//! [`futures_core::Stream`] that yields the current value immediately and
+//! then one item per subsequent `set`.
+//!
+//! # Implementation
+//!
+//! We register an internal subscriber via `Signal::subscribe`, which records
+//! its `SubscriberId` in the same subscriber set that memos and effects join
+//! when they read the signal. Its notify callback pushes `get_untracked()`
+//! into a queue shared with the stream and wakes whichever task is currently
+//! polling it. Dropping the stream unsubscribes it, so it doesn't keep the
+//! signal's notifier list growing forever.
+
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+use futures_core::Stream;
+
+use super::signal::Signal;
+use super::subscriber::SubscriberId;
+
+struct StreamState<T> {
+    queue: VecDeque<T>,
+    waker: Option<Waker>,
+}
+
+/// A `Stream` of a [`Signal`]'s values, created by [`Signal::to_stream`].
+pub struct SignalStream<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    signal: Signal<T>,
+    subscriber_id: SubscriberId,
+    state: Arc<Mutex<StreamState<T>>>,
+    yielded_initial: bool,
+}
+
+impl<T> SignalStream<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    pub(crate) fn new(signal: &Signal<T>) -> Self {
+        let subscriber_id = SubscriberId::new();
+        let state = Arc::new(Mutex::new(StreamState {
+            queue: VecDeque::new(),
+            waker: None,
+        }));
+
+        let notify_state = Arc::clone(&state);
+        let notify_signal = signal.clone();
+        signal.subscribe(subscriber_id, move || {
+            let mut guard = notify_state.lock().expect("stream state lock poisoned");
+            guard.queue.push_back(notify_signal.get_untracked());
+            if let Some(waker) = guard.waker.take() {
+                waker.wake();
+            }
+        });
+
+        Self {
+            signal: signal.clone(),
+            subscriber_id,
+            state,
+            yielded_initial: false,
+        }
+    }
+}
+
+impl<T> Stream for SignalStream<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let this = self.get_mut();
+
+        // The first poll always yields the signal's value as of that poll,
+        // even if it hasn't changed since the stream was created. It is not
+        // the value at creation: a `set` between creation and the first poll
+        // is seen here and is also queued, so that value is yielded twice.
+        if !this.yielded_initial {
+            this.yielded_initial = true;
+            return Poll::Ready(Some(this.signal.get_untracked()));
+        }
+
+        let mut guard = this.state.lock().expect("stream state lock poisoned");
+        if let Some(value) = guard.queue.pop_front() {
+            Poll::Ready(Some(value))
+        } else {
+            guard.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl<T> Drop for SignalStream<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    fn drop(&mut self) {
+        self.signal.unsubscribe(self.subscriber_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Drives one `poll_next` to completion, the same thing
+    /// `StreamExt::next` would give us - pulled in by hand so these tests
+    /// don't need a `futures-util` dependency just for this one combinator.
+    async fn next<S: Stream + Unpin>(stream: &mut S) -> Option<S::Item> {
+        std::future::poll_fn(|cx| Pin::new(&mut *stream).poll_next(cx)).await
+    }
+
+    #[tokio::test]
+    async fn stream_yields_current_value_immediately() {
+        let signal = Signal::new(1);
+        let mut stream = signal.to_stream();
+
+        assert_eq!(next(&mut stream).await, Some(1));
+    }
+
+    #[tokio::test]
+    async fn stream_yields_one_item_per_set() {
+        let signal = Signal::new(0);
+        let mut stream = signal.to_stream();
+
+        assert_eq!(next(&mut stream).await, Some(0));
+
+        signal.set(1);
+        signal.set(2);
+
+        assert_eq!(next(&mut stream).await, Some(1));
+        assert_eq!(next(&mut stream).await, Some(2));
+    }
+
+    #[tokio::test]
+    async fn dropping_the_stream_unsubscribes() {
+        let signal = Signal::new(0);
+        assert_eq!(signal.subscriber_count(), 0);
+
+        let stream = signal.to_stream();
+        assert_eq!(signal.subscriber_count(), 1);
+
+        drop(stream);
+        assert_eq!(signal.subscriber_count(), 0);
+    }
+}
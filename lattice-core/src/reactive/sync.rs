@@ -0,0 +1,25 @@
+//! Sync Primitive Abstraction
+//!
+//! A small indirection layer so the concurrency-sensitive parts of
+//! [`Memo`](super::memo::Memo) - the `RwLock`s guarding its shared state -
+//! compile against either `std::sync` (normal builds) or `loom::sync` (the
+//! `cfg(loom)` concurrency model checker used by `tests/loom_memo.rs`)
+//! without duplicating the locking logic itself.
+//!
+//! Only `RwLock` is re-exported here - `memo.rs` never names the guard
+//! types directly, so re-exporting them too would just be unused dead
+//! weight. `Memo`'s own
+//! `Arc<MemoInner<T>>` and `Arc<ReactiveHandle>` stay plain `std::sync::Arc`
+//! regardless of `cfg(loom)`, since they have to interoperate with
+//! [`Runtime::register`](super::runtime::Runtime::register), which takes a
+//! `std::sync::Arc<dyn Reactive>` and isn't loom-aware - converting it would
+//! mean modeling the entire runtime's registry under loom, not just the
+//! `Memo`-internal races this module exists to test. The `RwLock`-guarded
+//! fields inside `MemoInner` are the actual subject of those races, so
+//! that's all that needs to move.
+
+#[cfg(not(loom))]
+pub(crate) use std::sync::RwLock;
+
+#[cfg(loom)]
+pub(crate) use loom::sync::RwLock;
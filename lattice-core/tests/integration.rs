@@ -97,13 +97,12 @@ fn memo_depends_on_memo() {
     assert_eq!(doubled.get(), 10);
     assert_eq!(plus_ten.get(), 20);
 
-    // Update base signal
+    // Update base signal - propagates to both memos automatically: `doubled`
+    // is marked maybe-dirty directly, and `plus_ten` transitively through the
+    // subscriber edge its read of `doubled` recorded, with no manual
+    // `mark_dirty` needed.
     base_signal.set(10);
 
-    // Mark both memos dirty (in real system, this would be automatic)
-    doubled.mark_dirty();
-    plus_ten.mark_dirty();
-
     // Both should recompute
     assert_eq!(doubled.get(), 20);
     assert_eq!(plus_ten.get(), 30);
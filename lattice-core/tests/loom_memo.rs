@@ -0,0 +1,79 @@
+//! Loom Concurrency Model for Memo
+//!
+//! Exhaustively explores thread interleavings around `Memo`'s shared
+//! `RwLock`-guarded state, using `loom`'s mocked synchronization primitives
+//! in place of `std::sync` (see `reactive::sync`). Gated behind `cfg(loom)`
+//! so a plain `cargo test` skips this file entirely; run it with:
+//!
+//! ```text
+//! RUSTFLAGS="--cfg loom" cargo test --test loom_memo --release
+//! ```
+//!
+//! `dependencies`/`dependents` on a memo are plain `HashSet<u64>`/
+//! `HashSet<SubscriberId>`, not `Arc`-valued, so there's no literal `Arc`
+//! cycle possible there by construction - loom's leak check (run
+//! automatically at the end of every `loom::model`) instead meaningfully
+//! exercises the swapped `RwLock` fields themselves: any lock loom considers
+//! still "held" or otherwise undropped when a model iteration ends fails the
+//! model.
+
+#![cfg(loom)]
+
+use loom::thread;
+use lattice_core::reactive::{Memo, MemoState};
+
+/// A `get()` that recomputes on one thread racing a `mark_dirty()` on a
+/// clone from another must never let the dirty mark get silently lost - the
+/// race `MemoStatus`'s epoch guard exists to close (see "Avoiding Lost
+/// Updates" in `memo.rs`'s module docs).
+#[test]
+fn concurrent_get_and_mark_dirty_never_loses_the_dirty_mark() {
+    loom::model(|| {
+        let memo = Memo::new(|| 1);
+        let memo_for_dirty = memo.clone();
+
+        let getter = thread::spawn(move || {
+            memo.get();
+            memo
+        });
+
+        memo_for_dirty.mark_dirty();
+
+        let memo = getter.join().unwrap();
+
+        // Whatever interleaving happened, a memo that reports Clean must
+        // actually have a cached value - a lost dirty mark manifesting as
+        // "Clean but never recomputed after the mark" is exactly what this
+        // model is checking for.
+        if memo.state() == MemoState::Clean {
+            assert!(memo.has_value());
+        } else {
+            // The mark_dirty is still pending - a follow-up get must
+            // observe it rather than the race having dropped it entirely.
+            assert_eq!(memo.state(), MemoState::Dirty);
+            memo.get();
+            assert_eq!(memo.state(), MemoState::Clean);
+            assert!(memo.has_value());
+        }
+    });
+}
+
+/// Two clones of the same dirty memo calling `get()` concurrently must each
+/// observe a consistent final state - no torn value, and the memo settles
+/// `Clean` with a cached value once both threads have returned.
+#[test]
+fn concurrent_get_on_two_clones_settles_clean_with_a_value() {
+    loom::model(|| {
+        let memo_a = Memo::new(|| 42);
+        let memo_b = memo_a.clone();
+
+        let t1 = thread::spawn(move || memo_a.get());
+        let value = memo_b.get();
+        let other_value = t1.join().unwrap();
+
+        assert_eq!(value, 42);
+        assert_eq!(other_value, 42);
+        assert_eq!(memo_b.state(), MemoState::Clean);
+        assert!(memo_b.has_value());
+    });
+}